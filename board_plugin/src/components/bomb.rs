@@ -1,4 +1,7 @@
 use bevy::prelude::Component;
 
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component)]
-pub struct Bomb;
\ No newline at end of file
+pub struct Bomb {
+    /// Number of mines on the tile
+    pub count: u8
+}
\ No newline at end of file
@@ -0,0 +1,5 @@
+use bevy::prelude::Component;
+
+/// Marks the highlight sprite of the keyboard cursor
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component)]
+pub struct Cursor;
@@ -0,0 +1,8 @@
+use bevy::prelude::Component;
+
+/// Marks a tile cover as flagged by the player
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component)]
+pub struct Flag {
+    /// Number of mines the player put on the tile
+    pub count: u8
+}
\ No newline at end of file
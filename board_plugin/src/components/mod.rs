@@ -1,9 +1,12 @@
-pub use coordinates::Coordinates;
+pub use minesweeper_core::Coordinates;
 pub use bomb::Bomb;
 pub use bomb_neighbor::BombNeighbor;
+pub use cursor::Cursor;
+pub use flag::Flag;
 pub use uncover::Uncover;
 
-mod coordinates;
 mod bomb;
 mod bomb_neighbor;
+mod cursor;
+mod flag;
 mod uncover;
\ No newline at end of file
@@ -1,7 +1,87 @@
+use std::time::Duration;
 use bevy::ecs::event::Event;
 use crate::components::Coordinates;
+use crate::resources::{BoardOptions, PlaybackSpeed, Replay};
 
 #[derive(Debug, Copy, Clone, Event)]
 pub struct TileTriggerEvent{
     pub coordinates: Coordinates
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Copy, Clone, Event)]
+pub struct TileMarkEvent{
+    pub coordinates: Coordinates
+}
+
+#[derive(Debug, Copy, Clone, Event)]
+pub struct TileChordEvent{
+    pub coordinates: Coordinates
+}
+
+#[derive(Debug, Copy, Clone, Event)]
+pub struct BombExplosionEvent{
+    pub coordinates: Coordinates
+}
+
+#[derive(Debug, Copy, Clone, Event)]
+pub struct BoardCompletedEvent{
+    /// Time spent since the board was generated
+    pub elapsed: Duration,
+    /// Number of uncover clicks
+    pub uncover_clicks: u32,
+    /// Number of flag clicks
+    pub mark_clicks: u32,
+    /// Number of chord clicks
+    pub chord_clicks: u32,
+    /// Number of clicks that uncovered a tile or changed a flag
+    pub useful_clicks: u32,
+    /// Number of hints shown
+    pub hints_used: u32,
+    /// Minimum number of clicks clearing the board
+    pub three_bv: u32
+}
+
+impl BoardCompletedEvent {
+    /// Number of clicks of every kind
+    pub fn total_clicks(&self) -> u32 {
+        self.uncover_clicks + self.mark_clicks + self.chord_clicks
+    }
+
+    /// Board value cleared per second
+    pub fn three_bv_per_second(&self) -> f32 {
+        let seconds = self.elapsed.as_secs_f32();
+        if seconds == 0.0 {
+            return 0.0;
+        }
+        self.three_bv as f32 / seconds
+    }
+
+    /// Click efficiency: 3BV divided by the total clicks, above 1 when chording saves clicks
+    pub fn ioe(&self) -> f32 {
+        match self.total_clicks() {
+            0 => 0.0,
+            clicks => self.three_bv as f32 / clicks as f32
+        }
+    }
+}
+
+/// Asks for a tile that can be proven safe, or mined, from the uncovered tiles
+#[derive(Debug, Copy, Clone, Default, Event)]
+pub struct HintRequestEvent;
+
+/// Replaces the board with a new one, generated from `options` when set
+#[derive(Debug, Clone, Default, Event)]
+pub struct ResetBoardEvent{
+    pub options: Option<BoardOptions>
+}
+
+/// Replaces the board with the recorded one and plays its actions back
+#[derive(Debug, Clone, Event)]
+pub struct PlayReplayEvent{
+    pub replay: Replay,
+    pub speed: PlaybackSpeed
+}
+
+/// Plays the next action of a stepwise replay
+#[derive(Debug, Copy, Clone, Default, Event)]
+pub struct ReplayStepEvent;
@@ -0,0 +1,89 @@
+use bevy::math::Vec2;
+use minesweeper_core::Topology;
+
+use crate::Coordinates;
+
+/// Height of a pointy-top hex tile for a unit width, `2 / sqrt(3)`
+const HEX_HEIGHT: f32 = 1.154_700_5;
+/// Vertical distance between two hex rows for a unit width, `sqrt(3) / 2`
+const HEX_ROW_HEIGHT: f32 = 0.866_025_4;
+
+/// Size of a tile sprite of width `size`
+pub fn tile_size(topology: Topology, size: f32) -> Vec2 {
+    match topology {
+        Topology::Square => Vec2::splat(size),
+        Topology::Hex => Vec2::new(size, size * HEX_HEIGHT)
+    }
+}
+
+/// Size of a complete board of `width` by `height` tiles
+pub fn board_size(topology: Topology, (width, height): (u16, u16), size: f32) -> Vec2 {
+    match topology {
+        Topology::Square => Vec2::new(width as f32 * size, height as f32 * size),
+        Topology::Hex => {
+            // Odd rows stick out half a tile on the right
+            let shift = if height > 1 { size / 2. } else { 0. };
+            Vec2::new(
+                width as f32 * size + shift,
+                height.saturating_sub(1) as f32 * size * HEX_ROW_HEIGHT + size * HEX_HEIGHT
+            )
+        }
+    }
+}
+
+/// Center of the tile at `coordinates`, relative to the bottom left of the board
+pub fn tile_center(topology: Topology, coordinates: Coordinates, size: f32) -> Vec2 {
+    let (x, y) = (coordinates.x as f32, coordinates.y as f32);
+    match topology {
+        Topology::Square => Vec2::new(x * size + size / 2., y * size + size / 2.),
+        Topology::Hex => {
+            let shift = if coordinates.y % 2 == 1 { size / 2. } else { 0. };
+            Vec2::new(
+                x * size + size / 2. + shift,
+                y * size * HEX_ROW_HEIGHT + size * HEX_HEIGHT / 2.
+            )
+        }
+    }
+}
+
+/// Retrieves the tile under `position`, relative to the bottom left of the board
+pub fn tile_at(
+    topology: Topology,
+    (width, height): (u16, u16),
+    position: Vec2,
+    size: f32
+) -> Option<Coordinates> {
+    if position.x < 0. || position.y < 0. {
+        return None;
+    }
+    let coordinates = match topology {
+        Topology::Square => Coordinates {
+            x: (position.x / size) as u16,
+            y: (position.y / size) as u16
+        },
+        Topology::Hex => {
+            // The closest tile center among the rows around the position
+            let row = (position.y / (size * HEX_ROW_HEIGHT)) as i32;
+            let coordinates = (row - 1..=row + 1)
+                .filter(|y| *y >= 0)
+                .map(|y| {
+                    let shift = if y % 2 == 1 { size / 2. } else { 0. };
+                    let x = ((position.x - shift) / size).floor().max(0.);
+                    Coordinates { x: x as u16, y: y as u16 }
+                })
+                .min_by(|a, b| {
+                    let a = position.distance_squared(tile_center(topology, *a, size));
+                    let b = position.distance_squared(tile_center(topology, *b, size));
+                    a.total_cmp(&b)
+                })?;
+            // Positions past the board edges are outside of every hex
+            let delta = (position - tile_center(topology, coordinates, size)).abs();
+            let half_height = size * HEX_HEIGHT / 2.;
+            if delta.x > size / 2. || delta.y > half_height - delta.x * HEX_HEIGHT / 2. {
+                return None;
+            }
+            coordinates
+        }
+    };
+    (coordinates.x < width && coordinates.y < height).then_some(coordinates)
+}
\ No newline at end of file
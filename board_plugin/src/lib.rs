@@ -1,22 +1,35 @@
 pub mod components;
 pub mod resources;
+pub mod events;
 mod bounds;
-mod events;
+mod layout;
+mod states;
 mod systems;
 
+use std::fs;
+use std::time::Duration;
 use bevy::utils::HashMap;
-use bevy::app::{App, Plugin, Startup};
+use bevy::app::{App, Plugin};
 use bevy::asset::{AssetServer, Handle};
 use bevy::core::Name;
 use bevy::ecs::{
     entity::Entity,
+    event::EventReader,
     query::With,
-    system::{Commands, Query, Res}
+    schedule::{
+        common_conditions::{in_state, resource_exists},
+        IntoSystemConfigs,
+        NextState,
+        OnEnter,
+        OnExit,
+        States
+    },
+    system::{Commands, EntityCommands, Query, Res, ResMut}
 };
-use bevy::hierarchy::{BuildChildren, ChildBuilder};
+use bevy::hierarchy::{BuildChildren, ChildBuilder, DespawnRecursiveExt};
 use bevy::log;
 use bevy::math::{Vec2, Vec3};
-use bevy::prelude::Update;
+use bevy::prelude::{Last, PostUpdate, Update};
 use bevy::render::{
     color::Color,
     prelude::SpatialBundle,
@@ -25,73 +38,409 @@ use bevy::render::{
 };
 use bevy::sprite::{Anchor, SpriteBundle, Sprite};
 use bevy::text::{Font, Text2dBundle, Text, TextSection, TextStyle, TextAlignment};
+use bevy::time::Time;
 use bevy::transform::components::{Transform, GlobalTransform};
 use bevy::window::{PrimaryWindow, Window};
+use minesweeper_core::{Game, Tile, TileMap, Topology};
+use rand::{thread_rng, Rng};
 
-use components::{Bomb, BombNeighbor, Coordinates, Uncover};
+use components::{Bomb, BombNeighbor, Coordinates, Cursor, Flag, Uncover};
 use resources::{
     board::Board,
+    BoardError,
     BoardOptions,
     BoardPosition,
-    tile::Tile,
-    tile_map::TileMap,
+    KeyboardControls,
+    ReplayPlayback,
+    FileError,
+    SaveFile,
+    SavedGame,
     TileSize
 };
 use bounds::Bounds2;
 
-use crate::events::TileTriggerEvent;
+use crate::events::{
+    BoardCompletedEvent,
+    BombExplosionEvent,
+    HintRequestEvent,
+    PlayReplayEvent,
+    ReplayStepEvent,
+    ResetBoardEvent,
+    TileChordEvent,
+    TileMarkEvent,
+    TileTriggerEvent
+};
+pub use crate::states::GameState;
+
+/// Color of the covers of the covered tiles
+pub(crate) const COVER_COLOR: Color = Color::DARK_GRAY;
+/// Color of the uncovered tiles
+pub(crate) const TILE_COLOR: Color = Color::GRAY;
 
-pub struct BoardPlugin;
+/// Board plugin, generic over the app state it lives in
+pub struct BoardPlugin<T> {
+    /// State in which the board is generated and its systems run
+    pub running_state: T,
+    /// State in which the board is torn down
+    pub cleanup_state: T
+}
 
-impl Plugin for BoardPlugin {
+impl<T: States> Plugin for BoardPlugin<T> {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, Self::create_board);
-        app.add_systems(Update, systems::input::input_handling);
-        app.add_systems(Update, systems::uncover::trigger_event_handler);
-        app.add_systems(Update, systems::uncover::uncover_tiles);
+        app.add_state::<GameState>();
+        app.add_systems(OnEnter(self.running_state.clone()), Self::create_board);
+        app.add_systems(
+            Update,
+            (
+                systems::input::input_handling.run_if(systems::replay::no_playback),
+                systems::keyboard::keyboard_handling
+                    .run_if(systems::replay::no_playback)
+                    .run_if(resource_exists::<KeyboardControls>()),
+                systems::replay::play_replay.run_if(resource_exists::<ReplayPlayback>()),
+                systems::uncover::trigger_event_handler,
+                Self::spawn_generated_tiles.after(systems::uncover::trigger_event_handler),
+                systems::uncover::chord_event_handler,
+                systems::mark::mark_tiles,
+                systems::hint::hint_handler,
+            )
+                .run_if(in_state(self.running_state.clone()))
+                .run_if(in_state(GameState::Playing))
+                .run_if(resource_exists::<Board>())
+        );
+        app.add_systems(
+            Update,
+            (
+                systems::uncover::uncover_tiles,
+                systems::explosion::explosion_handler,
+                systems::completion::completion_check,
+                systems::stats::record_stats,
+            )
+                .chain()
+                .run_if(in_state(self.running_state.clone()))
+                .run_if(resource_exists::<Board>())
+        );
+        // The board is replaced after every other system has used it this frame
+        app.add_systems(
+            PostUpdate,
+            (Self::reset_board, Self::start_replay.after(Self::reset_board))
+                .run_if(in_state(self.running_state.clone()))
+        );
+        app.add_systems(
+            OnEnter(GameState::Paused),
+            systems::clock::pause_clock.run_if(resource_exists::<Board>())
+        );
+        app.add_systems(
+            OnExit(GameState::Paused),
+            systems::clock::resume_clock.run_if(resource_exists::<Board>())
+        );
+        app.add_systems(OnEnter(self.cleanup_state.clone()), Self::cleanup_board);
+        // Exit is requested during the frame, the game is saved at its very end
+        app.add_systems(Last, systems::save::autosave);
+        app.add_systems(Last, systems::replay::save_replay.run_if(resource_exists::<Board>()));
         app.add_event::<TileTriggerEvent>();
+        app.add_event::<TileMarkEvent>();
+        app.add_event::<TileChordEvent>();
+        app.add_event::<BombExplosionEvent>();
+        app.add_event::<BoardCompletedEvent>();
+        app.add_event::<HintRequestEvent>();
+        app.add_event::<ResetBoardEvent>();
+        app.add_event::<PlayReplayEvent>();
+        app.add_event::<ReplayStepEvent>();
         log::info!("Loaded Board Plugin");
     }
 }
 
-impl BoardPlugin {
-    /// System to generate the complete board
+impl<T> BoardPlugin<T> {
+    /// System to generate the complete board, or continue the saved game
+    #[allow(clippy::too_many_arguments)]
     pub fn create_board(
         mut commands: Commands,
+        board: Option<Res<Board>>,
         board_options: Option<Res<BoardOptions>>,
+        save_file: Option<Res<SaveFile>>,
         window_query: Query<&Window, With<PrimaryWindow>>,
-        asset_server: Res<AssetServer>
+        asset_server: Res<AssetServer>,
+        time: Res<Time>,
+        mut game_state: ResMut<NextState<GameState>>
     ) {
-
-        let font = asset_server.load("fonts/pixeled.ttf");
-        let bomb_image = asset_server.load("sprites/bomb.png");
-
+        // A board is kept while the running state is left without cleanup
+        if board.is_some() {
+            return;
+        }
         let options = match board_options {
             None => BoardOptions::default(),
             Some(o) => o.clone()
         };
-        let mut tile_map = TileMap::empty(
-            options.map_size.0, 
-            options.map_size.1
+        let window = window_query.get_single().unwrap();
+        // An autosaved game is continued instead of generating a new one
+        if let Some(save_file) = save_file {
+            match Self::load_save(&save_file) {
+                Ok(Some(saved)) => {
+                    log::info!("Continuing saved game from {}", save_file.0.display());
+                    let board = Self::resume_board(
+                        &mut commands,
+                        saved,
+                        window,
+                        &asset_server,
+                        time.elapsed()
+                    );
+                    commands.insert_resource(board);
+                    game_state.set(GameState::Playing);
+                    return;
+                }
+                Ok(None) => (),
+                Err(e) => log::error!("Failed to continue saved game: {}", e)
+            }
+        }
+        match Self::new_board(&mut commands, options, window, &asset_server) {
+            Ok(board) => {
+                // We add the main resource of the game, the board
+                commands.insert_resource(board);
+                game_state.set(GameState::Playing);
+            }
+            Err(e) => log::error!("Failed to generate board: {}", e)
+        }
+    }
+
+    /// Reads the save file once, removing it so that it is only continued once
+    fn load_save(save_file: &SaveFile) -> Result<Option<SavedGame>, FileError> {
+        if !save_file.0.exists() {
+            return Ok(None);
+        }
+        let saved = SavedGame::load(&save_file.0)?;
+        fs::remove_file(&save_file.0)?;
+        Ok(Some(saved))
+    }
+
+    /// System to replace the board with a freshly generated one
+    #[allow(clippy::too_many_arguments)]
+    pub fn reset_board(
+        mut commands: Commands,
+        board: Option<Res<Board>>,
+        board_options: Option<Res<BoardOptions>>,
+        mut reset_board_evr: EventReader<ResetBoardEvent>,
+        window_query: Query<&Window, With<PrimaryWindow>>,
+        asset_server: Res<AssetServer>,
+        mut game_state: ResMut<NextState<GameState>>
+    ) {
+        let Some(event) = reset_board_evr.iter().last() else {
+            return;
+        };
+        let options = match (&event.options, board_options) {
+            (Some(o), _) => o.clone(),
+            (None, Some(o)) => o.clone(),
+            (None, None) => BoardOptions::default()
+        };
+        // Invalid options keep the current board
+        if let Err(e) = options.resolve_bomb_count() {
+            log::error!("Failed to reset board: {}", e);
+            return;
+        }
+        log::info!("Resetting board");
+        let window = window_query.get_single().unwrap();
+        if Self::replace_board(
+            &mut commands,
+            board,
+            options.clone(),
+            window,
+            &asset_server,
+            &mut game_state
+        ) {
+            // New options are kept for the next boards once a board was generated from them
+            if event.options.is_some() {
+                commands.insert_resource(options);
+            }
+            commands.remove_resource::<ReplayPlayback>();
+        }
+    }
+
+    /// System to replace the board with a recorded one and play its actions back
+    pub fn start_replay(
+        mut commands: Commands,
+        board: Option<Res<Board>>,
+        mut play_replay_evr: EventReader<PlayReplayEvent>,
+        window_query: Query<&Window, With<PrimaryWindow>>,
+        asset_server: Res<AssetServer>,
+        mut game_state: ResMut<NextState<GameState>>
+    ) {
+        let Some(event) = play_replay_evr.iter().last() else {
+            return;
+        };
+        // The recorded options are not kept for the next boards
+        let options = event.replay.board_options();
+        if let Err(e) = options.resolve_bomb_count() {
+            log::error!("Failed to play replay: {}", e);
+            return;
+        }
+        log::info!("Playing replay of {} actions", event.replay.steps.len());
+        let window = window_query.get_single().unwrap();
+        if Self::replace_board(
+            &mut commands,
+            board,
+            options,
+            window,
+            &asset_server,
+            &mut game_state
+        ) {
+            commands.insert_resource(ReplayPlayback::new(event.replay.clone(), event.speed));
+        }
+    }
+
+    /// Generates a new board from `options`, replacing the current one only once generated.
+    /// Returns whether the board was replaced
+    fn replace_board(
+        commands: &mut Commands,
+        board: Option<Res<Board>>,
+        options: BoardOptions,
+        window: &Window,
+        asset_server: &AssetServer,
+        game_state: &mut NextState<GameState>
+    ) -> bool {
+        match Self::new_board(commands, options, window, asset_server) {
+            Ok(new_board) => {
+                if let Some(board) = board {
+                    commands.entity(board.entity).despawn_recursive();
+                }
+                commands.insert_resource(new_board);
+                game_state.set(GameState::Playing);
+                true
+            }
+            Err(e) => {
+                log::error!("Failed to generate board: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Generates a new game from `options` and spawns its board
+    fn new_board(
+        commands: &mut Commands,
+        options: BoardOptions,
+        window: &Window,
+        asset_server: &AssetServer
+    ) -> Result<Board, BoardError> {
+        let seed = options.seed.unwrap_or_else(|| thread_rng().gen());
+        let game = match &options.layout {
+            // Fixed layouts already hold their bombs
+            Some(layout) => Game::new(layout.load()?),
+            None => {
+                let tile_map = options.tile_map()?;
+                let bomb_count = options.bomb_count_for(&tile_map)?;
+                log::info!("board seed: {}", seed);
+                // With first click protection, bombs are placed once the first tile is triggered
+                // No-guess boards are only solvable from their opening, revealed below
+                Game::generate(
+                    tile_map,
+                    bomb_count,
+                    seed,
+                    options.safe_first_click,
+                    options.no_guess
+                )?
+            }
+        };
+        let (mut board, safe_start) = Self::spawn_board(
+            commands,
+            game,
+            options,
+            seed,
+            window,
+            asset_server
         );
-        tile_map.set_bombs(options.bomb_count);
+        if (board.options.safe_start || board.options.no_guess.is_some()) && !board.awaiting_first_click {
+            if let Some(coords) = safe_start {
+                let reveal = board.game.reveal(coords);
+                for entity in board.revealed_covers(&reveal) {
+                    commands.entity(entity).insert(Uncover {});
+                }
+            }
+        }
+        Ok(board)
+    }
+
+    /// Spawns the board of a saved game, with its flags and counters.
+    /// The clock keeps running from `now` if the saved game was started
+    fn resume_board(
+        commands: &mut Commands,
+        saved: SavedGame,
+        window: &Window,
+        asset_server: &AssetServer,
+        now: Duration
+    ) -> Board {
+        // The next boards are generated with the options of the continued game
+        commands.insert_resource(saved.options.clone());
+        let (mut board, _) = Self::spawn_board(
+            commands,
+            saved.game,
+            saved.options,
+            saved.seed,
+            window,
+            asset_server
+        );
+        board.resumed_elapsed = saved.elapsed;
+        if saved.elapsed > Duration::ZERO {
+            board.start_clock(now);
+        }
+        board.uncover_clicks = saved.uncover_clicks;
+        board.mark_clicks = saved.mark_clicks;
+        board.chord_clicks = saved.chord_clicks;
+        board.useful_clicks = saved.useful_clicks;
+        board.hints_used = saved.hints_used;
+        board.recording = saved.recording;
+        let flag_image = asset_server.load("sprites/white_flag.png");
+        let font = asset_server.load("fonts/pixeled.ttf");
+        for coords in board.game.flagged_tiles() {
+            if let Some(entity) = board.covered_tiles.get(&coords) {
+                systems::mark::flag_cover(
+                    commands,
+                    *entity,
+                    board.tile_size,
+                    flag_image.clone(),
+                    board.game.flag_count(coords),
+                    font.clone()
+                );
+            }
+        }
+        board
+    }
+
+    /// Spawns the board entity hierarchy of `game`, covering only its covered tiles.
+    /// Returns the matching `Board` resource and the first empty tile, a safe place to start
+    fn spawn_board(
+        commands: &mut Commands,
+        game: Game,
+        options: BoardOptions,
+        seed: u64,
+        window: &Window,
+        asset_server: &AssetServer
+    ) -> (Board, Option<Coordinates>) {
+        let font = asset_server.load("fonts/pixeled.ttf");
+        let bomb_image = asset_server.load("sprites/bomb.png");
+        let tile_image = match game.tile_map().topology() {
+            Topology::Square => Handle::default(),
+            Topology::Hex => asset_server.load("sprites/hexagon.png")
+        };
+
+        let awaiting_first_click = !game.is_generated();
+        let tile_map = game.tile_map();
         #[cfg(feature = "debug")]
-        log::info!("{}", tile_map.console_output());
+        if !awaiting_first_click {
+            log::info!("{}", tile_map.console_output());
+        }
 
         // We define the size of our tiles in world space
-        let window = window_query.get_single().unwrap();
         let tile_size = match options.tile_size {
             TileSize::Fixed(v) => v,
             TileSize::Adaptive { min, max } => Self::adaptive_tile_size(
-                window.clone(),
+                window,
                 (min, max),
-                (tile_map.width(), tile_map.height()),
+                tile_map,
             ),
         };
         // We deduce the size of the complete board
-        let board_size = Vec2::new(
-            tile_map.width() as f32 * tile_size,
-            tile_map.height() as f32 * tile_size,
+        let board_size = layout::board_size(
+            tile_map.topology(),
+            (tile_map.width(), tile_map.height()),
+            tile_size
         );
         log::info!("board size: {}", board_size);
         // We define the board anchor position (bottom left)
@@ -102,166 +451,273 @@ impl BoardPlugin {
             BoardPosition::Custom(p) => p,
         };
 
-        let mut covered_tiles = HashMap::with_capacity(
-            ( tile_map.width() * tile_map.height() ).into()
-        );
+        let mut covered_tiles = HashMap::with_capacity(tile_map.tile_count());
 
         let mut safe_start = None;
 
-        commands.spawn(
+        let board_entity = commands.spawn(
             SpatialBundle {
                 visibility: Visibility::Visible,
-                transform: Transform::from_translation(board_position.into()),
+                transform: Transform::from_translation(board_position),
                 ..Default::default()
             }
         ).insert(Name::new("Board"))
         .insert(Transform::from_translation(board_position))
         .insert(GlobalTransform::default())
         .with_children(|parent| {
-            // We spawn the board background sprite at the center of the board, since the sprite pivot is centered
-            parent.spawn(SpriteBundle {
-                    sprite: Sprite {
-                        color: Color::WHITE,
-                        custom_size: Some(board_size),
+            // We spawn the board background sprite at the center of the board, since the sprite pivot is centered.
+            // Shaped boards have none, to leave their holes empty
+            if tile_map.mask().is_none() {
+                parent.spawn(SpriteBundle {
+                        sprite: Sprite {
+                            color: Color::WHITE,
+                            custom_size: Some(board_size),
+                            ..Default::default()
+                        },
+                        transform: Transform::from_xyz(board_size.x / 2., board_size.y / 2., 0.),
                         ..Default::default()
-                    },
-                    transform: Transform::from_xyz(board_size.x / 2., board_size.y / 2., 0.),
+                }).insert(Name::new("Background"));
+            }
+
+            // The keyboard cursor is shown once moved
+            parent.spawn(SpriteBundle {
+                sprite: Sprite {
+                    color: Color::rgba(1.0, 1.0, 0.0, 0.4),
+                    custom_size: Some(layout::tile_size(tile_map.topology(), tile_size)),
                     ..Default::default()
-            }).insert(Name::new("Background"));
+                },
+                texture: tile_image.clone(),
+                transform: Transform::from_xyz(0., 0., 5.),
+                visibility: Visibility::Hidden,
+                ..Default::default()
+            })
+            .insert(Name::new("Cursor"))
+            .insert(Cursor);
 
             Self::spawn_tiles(
                 parent,
-                &tile_map,
+                &game,
                 tile_size,
                 options.tile_padding,
-                Color::DARK_GRAY,
+                COVER_COLOR,
                 &mut covered_tiles,
-                Color::GRAY,
+                TILE_COLOR,
+                tile_image,
                 bomb_image,
                 font,
                 &mut safe_start
             );
-        });
-        
-        if options.safe_start {
-            if let Some(entity) = safe_start {
-                commands.entity(entity).insert(Uncover {});
-            }
-        }
+        })
+        .id();
         
-        // We add the main resource of the game, the board
-        commands.insert_resource(Board {
-            tile_map,
+        let board = Board {
+            game,
             bounds: Bounds2 {
                 position: Vec2::new(board_position.x, board_position.y),
                 size: board_size,
             },
             tile_size,
-            covered_tiles
-        })
+            covered_tiles,
+            entity: board_entity,
+            started_at: None,
+            paused_at: None,
+            paused_elapsed: Duration::ZERO,
+            uncover_clicks: 0,
+            mark_clicks: 0,
+            chord_clicks: 0,
+            useful_clicks: 0,
+            hints_used: 0,
+            hint: None,
+            cursor: None,
+            awaiting_first_click,
+            resumed_elapsed: Duration::ZERO,
+            recording: Vec::new(),
+            options,
+            seed
+        };
+        (board, safe_start)
+    }
 
+    /// System spawning the tile contents once the first click placed the bombs
+    pub fn spawn_generated_tiles(
+        mut commands: Commands,
+        mut board: ResMut<Board>,
+        tiles: Query<(Entity, &Coordinates)>,
+        asset_server: Res<AssetServer>
+    ) {
+        if !board.awaiting_first_click || !board.game.is_generated() {
+            return;
+        }
+        board.awaiting_first_click = false;
+        #[cfg(feature = "debug")]
+        log::info!("{}", board.game.tile_map().console_output());
+
+        let size = board.tile_size - board.options.tile_padding;
+        let font: Handle<Font> = asset_server.load("fonts/pixeled.ttf");
+        let bomb_image: Handle<Image> = asset_server.load("sprites/bomb.png");
+        for (entity, coordinates) in tiles.iter() {
+            if let Some(tile) = board.game.tile_map().tile_at(*coordinates) {
+                Self::spawn_tile_content(
+                    &mut commands.entity(entity),
+                    tile,
+                    size,
+                    bomb_image.clone(),
+                    font.clone()
+                );
+            }
+        }
+    }
+
+    /// System to tear down the board entity hierarchy and resource
+    pub fn cleanup_board(
+        mut commands: Commands,
+        board: Option<Res<Board>>,
+        mut game_state: ResMut<NextState<GameState>>
+    ) {
+        if let Some(board) = board {
+            commands.entity(board.entity).despawn_recursive();
+            commands.remove_resource::<Board>();
+        }
+        commands.remove_resource::<ReplayPlayback>();
+        game_state.set(GameState::Idle);
     }
 
     /// Computes a tile size that matches the window according to the tile map size
     fn adaptive_tile_size (
-        window: Window,
+        window: &Window,
         (min, max): (f32, f32),
-        (width, height): (u16, u16)
+        tile_map: &TileMap
     ) -> f32 {
-        let max_width = window.width() / width as f32;
-        let max_height = window.height() / height as f32;
+        let unit_size = layout::board_size(
+            tile_map.topology(),
+            (tile_map.width(), tile_map.height()),
+            1.0
+        );
+        let max_width = window.width() / unit_size.x;
+        let max_height = window.height() / unit_size.y;
         max_width.min(max_height).clamp(min, max)
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn spawn_tiles(
         parent: &mut ChildBuilder,
-        tile_map: &TileMap,
+        game: &Game,
         size: f32,
         padding: f32,
         covered_tile_color: Color,
         covered_tiles: &mut HashMap<Coordinates, Entity>,
         color: Color,
+        tile_image: Handle<Image>,
         bomb_image: Handle<Image>,
         font: Handle<Font>,
-        safe_start_entity: &mut Option<Entity>
+        safe_start: &mut Option<Coordinates>
     ) {
-        // Tiles
+        let tile_map = game.tile_map();
+        let topology = tile_map.topology();
+        let sprite_size = layout::tile_size(topology, size - padding);
+        // Tiles, masked holes having none
         for (y, line) in tile_map.iter().enumerate() {
-            for (x, tile) in line.iter().enumerate() {
+            for x in 0..line.len() {
                 let coordinates = Coordinates {
                     x: x as u16,
                     y: y as u16,
                 };
+                let Some(tile) = tile_map.tile_at(coordinates) else {
+                    continue;
+                };
+                let position = layout::tile_center(topology, coordinates, size);
                 let mut cmd = parent.spawn(
                     SpriteBundle {
                         sprite: Sprite {
-                            color: Color::GRAY,
-                            custom_size: Some(Vec2::splat(
-                                size - padding,
-                            )),
+                            color,
+                            custom_size: Some(sprite_size),
                             ..Default::default()
                         },
-                        transform: Transform::from_xyz(
-                            (x as f32 * size) + (size / 2.),
-                            (y as f32 * size) + (size / 2.),
-                            1.,
-                        ),
+                        transform: Transform::from_xyz(position.x, position.y, 1.),
+                        texture: tile_image.clone(),
                         ..Default::default()
                     }
                 );
                 cmd.insert(Name::new(format!("Tile ({}, {})", x, y)));
                 cmd.insert(coordinates);
-                cmd.with_children(|parent| {
-                    let entity = parent
-                        .spawn(
-                            SpriteBundle {
-                                sprite: Sprite {
-                                    custom_size: Some(Vec2::splat(size - padding)),
-                                    color: covered_tile_color,
-                                    ..Default::default()
-                                },
-                                transform: Transform::from_xyz(0.0, 0.0, 2.0),
-                                ..Default::default()
-                            })
-                        .insert(Name::new("Tile Cover"))
-                        .id();
-                    covered_tiles.insert(coordinates, entity);
-                    if safe_start_entity.is_none() && *tile == Tile::Empty {
-                        *safe_start_entity = Some(entity);
-                    }
-                });
-                match tile {
-                    // If the tile is a bomb we add the matching component and a sprite child
-                    Tile::Bomb => {
-                        cmd
-                            .insert(Bomb {})
-                            .with_children(|parent| {
-                                parent.spawn(SpriteBundle {
+                if safe_start.is_none() && *tile == Tile::Empty {
+                    *safe_start = Some(coordinates);
+                }
+                // Tiles uncovered in a resumed game have no cover
+                if game.is_covered(coordinates) {
+                    cmd.with_children(|parent| {
+                        let entity = parent
+                            .spawn(
+                                SpriteBundle {
                                     sprite: Sprite {
-                                        custom_size: Some(Vec2::splat(size - padding)),
+                                        custom_size: Some(sprite_size),
+                                        color: covered_tile_color,
                                         ..Default::default()
                                     },
-                                    transform: Transform::from_xyz(0., 0., 1.),
-                                    texture: bomb_image.clone(),
+                                    transform: Transform::from_xyz(0.0, 0.0, 2.0),
+                                    texture: tile_image.clone(),
                                     ..Default::default()
-                                });
-                            });
-                    }
-                    // If the tile is a bomb neighbour we add the matching component and a text child
-                    Tile::BombNeighbor(v) => {
-                        cmd
-                            .insert(BombNeighbor { count: *v })
-                            .with_children(|parent| {
-                                parent.spawn(Self::bomb_count_text_bundle(
-                                    *v,
-                                    font.clone(),
-                                    size - padding,
-                                ));
-                            });
-                    }
-                    Tile::Empty => (),
+                                })
+                            .insert(Name::new("Tile Cover"))
+                            .id();
+                        covered_tiles.insert(coordinates, entity);
+                    });
                 }
+                Self::spawn_tile_content(
+                    &mut cmd,
+                    tile,
+                    size - padding,
+                    bomb_image.clone(),
+                    font.clone()
+                );
+            }
+        }
+    }
+
+    /// Adds the components and sprites matching `tile` to a tile entity
+    fn spawn_tile_content(
+        cmd: &mut EntityCommands,
+        tile: &Tile,
+        size: f32,
+        bomb_image: Handle<Image>,
+        font: Handle<Font>
+    ) {
+        match tile {
+            // If the tile is a bomb we add the matching component and a sprite child
+            Tile::Bomb(count) => {
+                cmd
+                    .insert(Bomb { count: *count })
+                    .with_children(|parent| {
+                        parent.spawn(SpriteBundle {
+                            sprite: Sprite {
+                                custom_size: Some(Vec2::splat(size)),
+                                ..Default::default()
+                            },
+                            transform: Transform::from_xyz(0., 0., 1.),
+                            texture: bomb_image,
+                            ..Default::default()
+                        });
+                        // Multi-mine tiles show their mine count over the bomb
+                        if *count > 1 {
+                            let mut text = Self::bomb_count_text_bundle(*count, font, size);
+                            text.transform.translation.z = 2.;
+                            parent.spawn(text);
+                        }
+                    });
+            }
+            // If the tile is a bomb neighbour we add the matching component and a text child
+            Tile::BombNeighbor(v) => {
+                cmd
+                    .insert(BombNeighbor { count: *v })
+                    .with_children(|parent| {
+                        parent.spawn(Self::bomb_count_text_bundle(
+                            *v,
+                            font,
+                            size,
+                        ));
+                    });
             }
+            Tile::Empty => (),
         }
     }
 
@@ -275,9 +731,15 @@ impl BoardPlugin {
                 2 => Color::GREEN,
                 3 => Color::YELLOW,
                 4 => Color::ORANGE,
-                _ => Color::PURPLE,
+                5 => Color::PURPLE,
+                6 => Color::CYAN,
+                7 => Color::PINK,
+                8 => Color::SALMON,
+                _ => Color::CRIMSON,
             },
         );
+        // Larger neighborhoods give multi-digit counts, shrunk to fit the tile
+        let font_size = size / text.len() as f32;
         // We generate a text bundle
         Text2dBundle {
             text: Text {
@@ -286,7 +748,7 @@ impl BoardPlugin {
                     style: TextStyle {
                         color,
                         font,
-                        font_size: size,
+                        font_size,
                     },
                 }],
                 alignment: TextAlignment::Center,
@@ -1,3 +1,4 @@
+use std::time::Duration;
 use bevy::utils::HashMap;
 
 use bevy::math::Vec2;
@@ -6,23 +7,54 @@ use bevy::ecs::{
     entity::Entity
 };
 use bevy::window::Window;
+use minesweeper_core::{Game, Reveal, Solver};
 
 use crate::bounds::Bounds2;
-use crate::{Coordinates, TileMap};
+use crate::layout;
+use crate::{BoardOptions, Coordinates};
+use crate::resources::{ReplayAction, ReplayStep};
 
 #[derive(Debug, Resource)]
 pub struct Board {
-    pub tile_map: TileMap,
+    /// Game rules and tile states
+    pub game: Game,
     pub bounds: Bounds2,
     pub tile_size: f32,
-    pub covered_tiles: HashMap<Coordinates, Entity>
+    /// Cover entities of the tiles that are still covered
+    pub covered_tiles: HashMap<Coordinates, Entity>,
+    pub entity: Entity,
+    /// Time elapsed since app startup at the first reveal, `None` until the clock is started
+    pub started_at: Option<Duration>,
+    /// Time elapsed since app startup when the game was paused, if it is
+    pub paused_at: Option<Duration>,
+    /// Time spent paused since the clock was started
+    pub paused_elapsed: Duration,
+    pub uncover_clicks: u32,
+    pub mark_clicks: u32,
+    pub chord_clicks: u32,
+    /// Clicks that uncovered a tile or changed a flag
+    pub useful_clicks: u32,
+    pub hints_used: u32,
+    /// Tile currently highlighted by a hint
+    pub hint: Option<Coordinates>,
+    /// Tile under the keyboard cursor, `None` until the cursor is moved
+    pub cursor: Option<Coordinates>,
+    /// Tile contents are not spawned until the bombs are placed on first click
+    pub awaiting_first_click: bool,
+    /// Time spent on the board before it was resumed from a save
+    pub resumed_elapsed: Duration,
+    /// Player actions on the tiles, in order
+    pub recording: Vec<ReplayStep>,
+    /// Options the board was generated with
+    pub options: BoardOptions,
+    /// Seed used to place the bombs
+    pub seed: u64
 }
 
 impl Board {
     /// Translates a mouse position to board coordinates
     pub fn mouse_position(&self, window: &Window, position: Vec2) -> Option<Coordinates> {
         // Window to world space
-        let window_size = Vec2::new(window.width(), window.height());
         let position = Vec2::new(
             position.x - window.width() / 2.0,
             window.height() / 2.0 - position.y
@@ -33,16 +65,59 @@ impl Board {
             return None;
         }
         // World space to board space
-        let coordinates = position - self.bounds.position;
-        Some(Coordinates {
-            x: (coordinates.x / self.tile_size) as u16,
-            y: (coordinates.y / self.tile_size) as u16,
-        })
+        let tile_map = self.game.tile_map();
+        layout::tile_at(
+            tile_map.topology(),
+            (tile_map.width(), tile_map.height()),
+            position - self.bounds.position,
+            self.tile_size
+        )
+        // Masked holes cannot be clicked
+        .filter(|c| tile_map.exists(*c))
+    }
+
+    /// Time spent playing on the board, `now` being the time elapsed since app startup.
+    /// Time before the first reveal and while paused is not counted
+    pub fn elapsed(&self, now: Duration) -> Duration {
+        let Some(started_at) = self.started_at else {
+            return self.resumed_elapsed;
+        };
+        let now = self.paused_at.unwrap_or(now);
+        now.saturating_sub(started_at).saturating_sub(self.paused_elapsed) + self.resumed_elapsed
+    }
+
+    /// Appends a player action to the recording, `now` being the time elapsed since app startup
+    pub fn record(&mut self, action: ReplayAction, now: Duration) {
+        let at = self.elapsed(now);
+        self.recording.push(ReplayStep { at, action });
     }
 
-    /// Retrieves a covered tile entity
-    pub fn tile_to_uncover(&self, coords: &Coordinates) -> Option<&Entity> {
-        self.covered_tiles.get(coords)
+    /// Starts the clock unless it is already running
+    pub fn start_clock(&mut self, now: Duration) {
+        if self.started_at.is_none() {
+            self.started_at = Some(now);
+        }
+    }
+
+    /// Stops the clock until `resume_clock`
+    pub fn pause_clock(&mut self, now: Duration) {
+        if self.started_at.is_some() && self.paused_at.is_none() {
+            self.paused_at = Some(now);
+        }
+    }
+
+    /// Restarts the clock stopped by `pause_clock`
+    pub fn resume_clock(&mut self, now: Duration) {
+        if let Some(paused_at) = self.paused_at.take() {
+            self.paused_elapsed += now.saturating_sub(paused_at);
+        }
+    }
+
+    /// We try to cycle the flag on a covered tile, returning the cover entity and the new flag count
+    pub fn try_toggle_mark(&mut self, coords: &Coordinates) -> Option<(Entity, u8)> {
+        let mark = self.game.flag(*coords)?;
+        let entity = *self.covered_tiles.get(coords)?;
+        Some((entity, mark))
     }
 
     /// We try to uncover a tile, returning the entity
@@ -50,11 +125,31 @@ impl Board {
         self.covered_tiles.remove(coords)
     }
 
-    /// We retrieve the adjacent covered tile entities of `coord`
-    pub fn adjacent_covered_tiles(&self, coord: Coordinates) -> Vec<Entity> {
-        self.tile_map
-            .safe_square_at(coord)
-            .filter_map(|c| self.covered_tiles.get(&c))
+    /// We look for a covered tile proven safe, or else proven mined, returning it with `true` when mined.
+    /// Only the visible state is used, never the hidden tiles
+    pub fn find_hint(&self) -> Option<(Coordinates, bool)> {
+        let knowledge = self.game.knowledge();
+        let solution = Solver::new(&knowledge).solve();
+        let safe = solution
+            .safe
+            .into_iter()
+            .find(|c| !self.game.is_flagged(*c))
+            .map(|c| (c, false));
+        safe.or_else(|| {
+            solution
+                .mines
+                .into_iter()
+                .find(|c| !self.game.is_flagged(*c))
+                .map(|c| (c, true))
+        })
+    }
+
+    /// We retrieve the cover entities of the tiles uncovered by `reveal`
+    pub fn revealed_covers(&self, reveal: &Reveal) -> Vec<Entity> {
+        reveal
+            .uncovered
+            .iter()
+            .filter_map(|c| self.covered_tiles.get(c))
             .copied()
             .collect()
     }
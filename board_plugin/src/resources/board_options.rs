@@ -1,6 +1,13 @@
+use std::fs;
+use bevy::asset::FileAssetIo;
 use bevy::prelude::{Vec3, Resource};
+use bevy::render::texture::{CompressedImageFormats, Image, ImageType, TextureFormatPixelInfo};
+use bevy::render::render_resource::TextureFormat;
+use minesweeper_core::{Game, Mask, TileMap};
 use serde::{Deserialize, Serialize};
 
+use crate::resources::{BoardError, Neighborhood, NoGuess, SafeFirstClick, Topology, Wrap};
+
 /// tile size options
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TileSize {
@@ -10,6 +17,34 @@ pub enum TileSize {
     Adaptive { min: f32, max: f32 }
 }
 
+/// Bomb count options
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum BombCount {
+    /// Fixed bomb count
+    Fixed(u16),
+    /// Ratio of bombs to tiles, strictly between 0 and 1
+    Density(f32)
+}
+
+/// Board shape options, for boards that are not rectangles
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum BoardMask {
+    /// ASCII layout, top line first, where `#` is a tile and `.` or a space is a hole
+    Layout(String),
+    /// Path in `assets/` of a PNG, where opaque pixels are tiles, or else of an ASCII layout
+    File(String)
+}
+
+/// Fixed board layouts, in the text format of `TileMap::from_text`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum BoardLayout {
+    /// Text board: a `WIDTHxHEIGHT` line, then the rows, top row first, with `*` for mines
+    /// and `.` for safe tiles
+    Text(String),
+    /// Path in `assets/` of a text board
+    File(String)
+}
+
 /// Board position customization options
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum BoardPosition {
@@ -19,13 +54,37 @@ pub enum BoardPosition {
     Custom(Vec3)
 }
 
+/// Standard difficulty presets
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Difficulty {
+    /// 9x9 tiles with 10 bombs
+    Beginner,
+    /// 16x16 tiles with 40 bombs
+    Intermediate,
+    /// 30x16 tiles with 99 bombs
+    Expert
+}
+
 /// Board generation options; must be used as a resource.
 #[derive(Debug, Clone, Serialize, Deserialize, Resource)]
 pub struct BoardOptions {
-    /// Tile map size
+    /// Tile map size, replaced by the mask size when set
     pub map_size: (u16, u16),
+    /// Board shape, rectangular when `None`
+    pub mask: Option<BoardMask>,
+    /// Fixed mine layout, replacing the map size, mask, bomb count and bomb placement options.
+    /// Such boards are square, without wrap and with the default neighborhood
+    pub layout: Option<BoardLayout>,
+    /// Tile lattice, square or hex
+    pub topology: Topology,
+    /// Map edges joined together, leaving no edge tiles
+    pub wrap: Wrap,
+    /// Tiles counted as neighbors on square maps
+    pub neighborhood: Neighborhood,
+    /// Maximum number of mines on a bomb tile, between 1 and 3
+    pub max_mines_per_tile: u8,
     /// Bomb count
-    pub bomb_count: u16,
+    pub bomb_count: BombCount,
     /// Board world position
     pub position: BoardPosition,
     /// Tile world size
@@ -33,7 +92,13 @@ pub struct BoardOptions {
     /// Padding between tiles
     pub tile_padding: f32,
     /// Does the board generate a safe place to start
-    pub safe_start: bool
+    pub safe_start: bool,
+    /// Does the board delay bomb placement to protect the first click
+    pub safe_first_click: SafeFirstClick,
+    /// Only keeps boards that can be cleared without guessing
+    pub no_guess: Option<NoGuess>,
+    /// Bomb placement seed, random when `None`
+    pub seed: Option<u64>
 }
 
 impl Default for TileSize {
@@ -54,11 +119,172 @@ impl Default for BoardOptions {
     fn default() -> Self {
         Self {
             map_size: (15, 15),
-            bomb_count: 30,
+            mask: None,
+            layout: None,
+            topology: Default::default(),
+            wrap: Default::default(),
+            neighborhood: Default::default(),
+            max_mines_per_tile: 1,
+            bomb_count: BombCount::Fixed(30),
             position: Default::default(),
             tile_size: Default::default(),
             tile_padding: 0.0,
-            safe_start: false
+            safe_start: false,
+            safe_first_click: Default::default(),
+            no_guess: None,
+            seed: None
+        }
+    }
+}
+
+impl BoardMask {
+    /// Loads the mask, reading files synchronously from the asset folder
+    pub fn load(&self) -> Result<Mask, BoardError> {
+        let path = match self {
+            Self::Layout(layout) => return Mask::from_ascii(layout),
+            Self::File(path) => path
+        };
+        let full_path = FileAssetIo::get_base_path().join("assets").join(path);
+        let bytes = fs::read(&full_path)
+            .map_err(|e| BoardError::InvalidMask(format!("{}: {}", full_path.display(), e)))?;
+        if !path.to_lowercase().ends_with(".png") {
+            let layout = String::from_utf8(bytes)
+                .map_err(|e| BoardError::InvalidMask(format!("{}: {}", path, e)))?;
+            return Mask::from_ascii(&layout);
         }
+        let image = Image::from_buffer(
+            &bytes,
+            ImageType::Extension("png"),
+            CompressedImageFormats::NONE,
+            true
+        )
+            .ok()
+            .and_then(|image| image.convert(TextureFormat::Rgba8UnormSrgb))
+            .ok_or_else(|| BoardError::InvalidMask(format!("{}: unreadable image", path)))?;
+        let (width, height) = (
+            image.texture_descriptor.size.width,
+            image.texture_descriptor.size.height
+        );
+        let max_size = TileMap::MAX_SIZE as u32;
+        if width > max_size || height > max_size {
+            return Err(BoardError::InvalidMask(format!(
+                "{}: {}x{} image exceeds {} pixels per side",
+                path, width, height, max_size
+            )));
+        }
+        let (width, height) = (width as u16, height as u16);
+        let pixel_size = image.texture_descriptor.format.pixel_size();
+        // Image rows start from the top, map rows from the bottom
+        Ok(Mask::from_fn(width, height, |c| {
+            let row = (height - 1 - c.y) as usize;
+            let pixel = (row * width as usize + c.x as usize) * pixel_size;
+            image.data[pixel + 3] >= 128
+        }))
+    }
+}
+
+impl BoardLayout {
+    /// Loads the layout, reading files synchronously from the asset folder
+    pub fn load(&self) -> Result<TileMap, BoardError> {
+        let tile_map = match self {
+            Self::Text(text) => TileMap::from_text(text)?,
+            Self::File(path) => {
+                let full_path = FileAssetIo::get_base_path().join("assets").join(path);
+                let text = fs::read_to_string(&full_path).map_err(|e| BoardError::InvalidText {
+                    line: 1,
+                    column: 1,
+                    reason: format!("{}: {}", full_path.display(), e)
+                })?;
+                TileMap::from_text(&text)?
+            }
+        };
+        Game::validate(&tile_map, tile_map.bomb_count())?;
+        Ok(tile_map)
+    }
+}
+
+impl Difficulty {
+    /// Getter for the preset map size
+    pub const fn map_size(&self) -> (u16, u16) {
+        match self {
+            Self::Beginner => (9, 9),
+            Self::Intermediate => (16, 16),
+            Self::Expert => (30, 16)
+        }
+    }
+
+    /// Getter for the preset bomb count
+    pub const fn bomb_count(&self) -> u16 {
+        match self {
+            Self::Beginner => 10,
+            Self::Intermediate => 40,
+            Self::Expert => 99
+        }
+    }
+}
+
+impl BoardOptions {
+    /// Default options with the map size and bomb count of `difficulty`
+    pub fn preset(difficulty: Difficulty) -> Self {
+        Self {
+            map_size: difficulty.map_size(),
+            bomb_count: BombCount::Fixed(difficulty.bomb_count()),
+            ..Default::default()
+        }
+    }
+
+    /// Retrieves the preset matching the map size and bomb count, if any.
+    /// Boards with other shapes or rules than the classic ones, or a fixed seed, match no preset
+    pub fn difficulty(&self) -> Option<Difficulty> {
+        let classic = self.mask.is_none()
+            && self.layout.is_none()
+            && self.topology == Topology::Square
+            && self.wrap == Wrap::None
+            && self.neighborhood == Neighborhood::Moore
+            && self.max_mines_per_tile == 1
+            && self.seed.is_none();
+        if !classic {
+            return None;
+        }
+        let bomb_count = self.resolve_bomb_count().ok()?;
+        [Difficulty::Beginner, Difficulty::Intermediate, Difficulty::Expert]
+            .into_iter()
+            .find(|d| d.map_size() == self.map_size && d.bomb_count() == bomb_count)
+    }
+
+    /// Builds the empty tile map matching the options, loading the mask if any
+    pub fn tile_map(&self) -> Result<TileMap, BoardError> {
+        let tile_map = match &self.mask {
+            Some(mask) => TileMap::from_mask(mask.load()?),
+            None => TileMap::empty(self.map_size.0, self.map_size.1)
+        };
+        Ok(tile_map
+            .with_topology(self.topology)
+            .with_wrap(self.wrap)
+            .with_neighborhood(self.neighborhood.clone())
+            .with_max_mines(self.max_mines_per_tile))
+    }
+
+    /// Validates the options, returning the number of bombs to place
+    pub fn resolve_bomb_count(&self) -> Result<u16, BoardError> {
+        if let Some(layout) = &self.layout {
+            return Ok(layout.load()?.bomb_count());
+        }
+        self.bomb_count_for(&self.tile_map()?)
+    }
+
+    /// Validates the options against their already built `tile_map`, returning the number of bombs to place
+    pub fn bomb_count_for(&self, tile_map: &TileMap) -> Result<u16, BoardError> {
+        let bomb_count = match self.bomb_count {
+            BombCount::Fixed(v) => v,
+            BombCount::Density(d) => {
+                if !(d > 0.0 && d < 1.0) {
+                    return Err(BoardError::InvalidDensity(d));
+                }
+                (tile_map.tile_count() as f32 * d).round() as u16
+            }
+        };
+        Game::validate(tile_map, bomb_count)?;
+        Ok(bomb_count)
     }
 }
\ No newline at end of file
@@ -0,0 +1,9 @@
+use bevy::prelude::Resource;
+
+/// Keyboard play options; when inserted, a cursor moved with the arrow keys or WASD
+/// reveals with space or enter, flags with F and chords with X
+#[derive(Debug, Copy, Clone, Default, Resource)]
+pub struct KeyboardControls {
+    /// Digits typed before a move repeat it, like vim counts
+    pub jump_counts: bool
+}
@@ -1,7 +1,23 @@
-pub(crate) mod tile;
-pub(crate) mod tile_map;
-
 pub use board_options::*;
+pub use keyboard_controls::*;
+pub use replay::*;
+pub use ron_file::FileError;
+pub use saved_game::*;
+pub use stats::*;
+pub use minesweeper_core::{
+    BoardError,
+    Neighborhood,
+    NoGuess,
+    NoGuessFallback,
+    SafeFirstClick,
+    Topology,
+    Wrap
+};
 
 pub mod board;
-mod board_options;
\ No newline at end of file
+mod board_options;
+mod keyboard_controls;
+mod replay;
+mod ron_file;
+mod saved_game;
+mod stats;
\ No newline at end of file
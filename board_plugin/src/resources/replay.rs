@@ -0,0 +1,107 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use bevy::prelude::Resource;
+use serde::{Deserialize, Serialize};
+
+use crate::components::Coordinates;
+use crate::resources::{board::Board, ron_file, BoardOptions, FileError};
+
+/// Replay file path; when inserted, every finished game is recorded there
+#[derive(Debug, Clone, Resource)]
+pub struct ReplayFile(pub PathBuf);
+
+/// Player action on a tile
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ReplayAction {
+    /// Uncovers the tile, as a `TileTriggerEvent`
+    Trigger(Coordinates),
+    /// Cycles the flag on the tile, as a `TileMarkEvent`
+    Mark(Coordinates),
+    /// Chords around the tile, as a `TileChordEvent`
+    Chord(Coordinates)
+}
+
+/// Recorded player action
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ReplayStep {
+    /// Time spent on the board when the action happened
+    pub at: Duration,
+    pub action: ReplayAction
+}
+
+/// Every action of a game, with what is needed to generate its board again
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Replay {
+    /// Options the board was generated with
+    pub options: BoardOptions,
+    /// Seed used to place the bombs
+    pub seed: u64,
+    pub steps: Vec<ReplayStep>
+}
+
+/// Replay playback pace
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PlaybackSpeed {
+    /// Actions are played at their recorded time, divided by the factor, 1.0 being real time.
+    /// Factors are clamped between 0 and 1000
+    Scaled(f32),
+    /// Actions are played one at a time, on every `ReplayStepEvent`
+    Stepwise
+}
+
+/// Replay being played back, player input being ignored meanwhile
+#[derive(Debug, Clone, Resource)]
+pub struct ReplayPlayback {
+    pub replay: Replay,
+    pub speed: PlaybackSpeed,
+    /// Index of the next step to play
+    pub next_step: usize,
+    /// Recorded time reached by the playback
+    pub clock: Duration
+}
+
+impl Replay {
+    /// Captures the actions recorded on `board`
+    pub fn from_board(board: &Board) -> Self {
+        Self {
+            options: board.options.clone(),
+            seed: board.seed,
+            steps: board.recording.clone()
+        }
+    }
+
+    /// Options generating the recorded board again
+    pub fn board_options(&self) -> BoardOptions {
+        BoardOptions {
+            seed: Some(self.seed),
+            ..self.options.clone()
+        }
+    }
+
+    /// Writes the replay to `path`, in RON
+    pub fn save(&self, path: &Path) -> Result<(), FileError> {
+        ron_file::save(self, path)
+    }
+
+    /// Reads a replay written by `save`
+    pub fn load(path: &Path) -> Result<Self, FileError> {
+        ron_file::load(path)
+    }
+}
+
+impl ReplayPlayback {
+    /// Starts playing back `replay` from its first step
+    pub fn new(replay: Replay, speed: PlaybackSpeed) -> Self {
+        Self {
+            replay,
+            speed,
+            next_step: 0,
+            clock: Duration::ZERO
+        }
+    }
+
+    /// Are there steps left to play?
+    pub fn is_finished(&self) -> bool {
+        self.next_step >= self.replay.steps.len()
+    }
+}
@@ -0,0 +1,46 @@
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::Path;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Errors raised when reading or writing the save, replay and statistics files
+#[derive(Debug)]
+pub enum FileError {
+    /// The file could not be read or written
+    Io(io::Error),
+    /// The file content does not match the expected data
+    Format(String)
+}
+
+/// Writes `value` to `path`, in RON
+pub(crate) fn save<T: Serialize>(value: &T, path: &Path) -> Result<(), FileError> {
+    let content = ron::ser::to_string_pretty(value, Default::default())
+        .map_err(|e| FileError::Format(e.to_string()))?;
+    fs::write(path, content)?;
+    Ok(())
+}
+
+/// Reads a value written by `save`
+pub(crate) fn load<T: DeserializeOwned>(path: &Path) -> Result<T, FileError> {
+    let content = fs::read_to_string(path)?;
+    ron::from_str(&content).map_err(|e| FileError::Format(e.to_string()))
+}
+
+impl From<io::Error> for FileError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl Display for FileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "file error: {}", e),
+            Self::Format(e) => write!(f, "invalid file content: {}", e)
+        }
+    }
+}
+
+impl Error for FileError {}
@@ -0,0 +1,61 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use bevy::prelude::Resource;
+use minesweeper_core::Game;
+use serde::{Deserialize, Serialize};
+
+use crate::resources::{board::Board, ron_file, BoardOptions, FileError, ReplayStep};
+
+/// Autosave file path; when inserted, in-progress games are saved on exit and continued on launch
+#[derive(Debug, Clone, Resource)]
+pub struct SaveFile(pub PathBuf);
+
+/// Complete state of an in-progress game
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedGame {
+    /// Options the board was generated with
+    pub options: BoardOptions,
+    /// Seed used to place the bombs
+    pub seed: u64,
+    /// Tile map with the covered and flagged tiles
+    pub game: Game,
+    /// Time spent on the board
+    pub elapsed: Duration,
+    pub uncover_clicks: u32,
+    pub mark_clicks: u32,
+    pub chord_clicks: u32,
+    #[serde(default)]
+    pub useful_clicks: u32,
+    pub hints_used: u32,
+    /// Player actions so far, continuing the replay of the game
+    #[serde(default)]
+    pub recording: Vec<ReplayStep>
+}
+
+impl SavedGame {
+    /// Captures the state of `board`, `now` being the time elapsed since app startup
+    pub fn from_board(board: &Board, now: Duration) -> Self {
+        Self {
+            options: board.options.clone(),
+            seed: board.seed,
+            game: board.game.clone(),
+            elapsed: board.elapsed(now),
+            uncover_clicks: board.uncover_clicks,
+            mark_clicks: board.mark_clicks,
+            chord_clicks: board.chord_clicks,
+            useful_clicks: board.useful_clicks,
+            hints_used: board.hints_used,
+            recording: board.recording.clone()
+        }
+    }
+
+    /// Writes the game to `path`, in RON
+    pub fn save(&self, path: &Path) -> Result<(), FileError> {
+        ron_file::save(self, path)
+    }
+
+    /// Reads a game written by `save`
+    pub fn load(path: &Path) -> Result<Self, FileError> {
+        ron_file::load(path)
+    }
+}
@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use bevy::prelude::Resource;
+use serde::{Deserialize, Serialize};
+
+use crate::resources::{ron_file, BoardOptions, Difficulty, FileError, SafeFirstClick};
+
+/// Statistics file path; when inserted, every finished game is counted there
+#[derive(Debug, Clone, Resource)]
+pub struct StatsFile(pub PathBuf);
+
+/// Group of boards sharing statistics
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum StatsBucket {
+    /// Classic board of a preset size and bomb count, kept apart for each first click protection
+    /// and with or without no-guess generation
+    Preset {
+        difficulty: Difficulty,
+        safe_first_click: SafeFirstClick,
+        no_guess: bool
+    },
+    /// Any other board
+    Custom
+}
+
+/// Results of the finished games of a bucket
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GameStats {
+    pub games_played: u32,
+    pub wins: u32,
+    /// Wins since the last loss
+    pub current_streak: u32,
+    pub best_streak: u32,
+    /// Fastest win
+    pub best_time: Option<Duration>,
+    /// Time spent on every won game together
+    pub total_win_time: Duration
+}
+
+/// Player statistics of every bucket
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Stats {
+    pub buckets: HashMap<StatsBucket, GameStats>
+}
+
+impl StatsBucket {
+    /// Retrieves the bucket of boards generated with `options`
+    pub fn of(options: &BoardOptions) -> Self {
+        match options.difficulty() {
+            Some(difficulty) => Self::Preset {
+                difficulty,
+                safe_first_click: options.safe_first_click,
+                no_guess: options.no_guess.is_some()
+            },
+            None => Self::Custom
+        }
+    }
+}
+
+impl GameStats {
+    /// Counts a finished game, `elapsed` being the time spent on it
+    pub fn record(&mut self, won: bool, elapsed: Duration) {
+        self.games_played += 1;
+        if !won {
+            self.current_streak = 0;
+            return;
+        }
+        self.wins += 1;
+        self.current_streak += 1;
+        self.best_streak = self.best_streak.max(self.current_streak);
+        self.best_time = Some(self.best_time.map_or(elapsed, |best| best.min(elapsed)));
+        self.total_win_time += elapsed;
+    }
+
+    /// Ratio of won games, between 0 and 1
+    pub fn win_rate(&self) -> f32 {
+        if self.games_played == 0 {
+            return 0.0;
+        }
+        self.wins as f32 / self.games_played as f32
+    }
+
+    /// Average time of the won games
+    pub fn average_time(&self) -> Option<Duration> {
+        (self.wins > 0).then(|| self.total_win_time / self.wins)
+    }
+}
+
+impl Stats {
+    /// Getter for the statistics of `bucket`
+    pub fn bucket(&self, bucket: StatsBucket) -> GameStats {
+        self.buckets.get(&bucket).cloned().unwrap_or_default()
+    }
+
+    /// Counts a finished game in `bucket`
+    pub fn record(&mut self, bucket: StatsBucket, won: bool, elapsed: Duration) -> &GameStats {
+        let stats = self.buckets.entry(bucket).or_default();
+        stats.record(won, elapsed);
+        stats
+    }
+
+    /// Writes the statistics to `path`, in RON
+    pub fn save(&self, path: &Path) -> Result<(), FileError> {
+        ron_file::save(self, path)
+    }
+
+    /// Reads the statistics written by `save`, empty when the file does not exist yet
+    pub fn load(path: &Path) -> Result<Self, FileError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        ron_file::load(path)
+    }
+}
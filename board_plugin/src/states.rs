@@ -0,0 +1,17 @@
+use bevy::ecs::schedule::States;
+
+/// Lifecycle of a single minesweeper game
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, States)]
+pub enum GameState {
+    /// No board has been generated
+    #[default]
+    Idle,
+    /// The board accepts player input
+    Playing,
+    /// The board is kept but ignores player input
+    Paused,
+    /// Every safe tile was uncovered
+    Won,
+    /// A bomb exploded
+    Lost
+}
\ No newline at end of file
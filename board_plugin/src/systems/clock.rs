@@ -0,0 +1,14 @@
+use bevy::ecs::system::{Res, ResMut};
+use bevy::time::Time;
+
+use crate::Board;
+
+/// Stops the board clock when the game is paused
+pub fn pause_clock(mut board: ResMut<Board>, time: Res<Time>) {
+    board.pause_clock(time.elapsed());
+}
+
+/// Restarts the board clock when the game is unpaused
+pub fn resume_clock(mut board: ResMut<Board>, time: Res<Time>) {
+    board.resume_clock(time.elapsed());
+}
@@ -0,0 +1,71 @@
+use bevy::asset::AssetServer;
+use bevy::ecs::{
+    event::EventWriter,
+    schedule::{NextState, State},
+    system::{Commands, Query, Res, ResMut}
+};
+use bevy::hierarchy::DespawnRecursiveExt;
+use bevy::log;
+use bevy::time::Time;
+use minesweeper_core::GameStatus;
+
+use crate::{Board, Flag, GameState};
+use crate::events::BoardCompletedEvent;
+use crate::systems::mark::flag_cover;
+
+#[allow(clippy::too_many_arguments)]
+pub fn completion_check(
+    mut commands: Commands,
+    board: Res<Board>,
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    flags: Query<&Flag>,
+    state: Res<State<GameState>>,
+    mut game_state: ResMut<NextState<GameState>>,
+    mut board_completed_ewr: EventWriter<BoardCompletedEvent>
+) {
+    if *state.get() != GameState::Playing || board.game.status() != GameStatus::Won {
+        return;
+    }
+    let event = BoardCompletedEvent {
+        elapsed: board.elapsed(time.elapsed()),
+        uncover_clicks: board.uncover_clicks,
+        mark_clicks: board.mark_clicks,
+        chord_clicks: board.chord_clicks,
+        useful_clicks: board.useful_clicks,
+        hints_used: board.hints_used,
+        three_bv: board.game.tile_map().analyze().three_bv
+    };
+    log::info!(
+        "Board completed in {:?}: 3BV {}, {:.2} 3BV/s, {} clicks ({} useful), IOE {:.2}",
+        event.elapsed,
+        event.three_bv,
+        event.three_bv_per_second(),
+        event.total_clicks(),
+        event.useful_clicks,
+        event.ioe()
+    );
+    // The game flagged the remaining mines, we add the missing flag sprites and fix the wrong counts
+    let flag_image = asset_server.load("sprites/white_flag.png");
+    let font = asset_server.load("fonts/pixeled.ttf");
+    for coords in board.game.flagged_tiles() {
+        let Some(entity) = board.covered_tiles.get(&coords) else {
+            continue;
+        };
+        let count = board.game.flag_count(coords);
+        if flags.get(*entity).is_ok_and(|flag| flag.count == count) {
+            continue;
+        }
+        commands.entity(*entity).despawn_descendants();
+        flag_cover(
+            &mut commands,
+            *entity,
+            board.tile_size,
+            flag_image.clone(),
+            count,
+            font.clone()
+        );
+    }
+    board_completed_ewr.send(event);
+    game_state.set(GameState::Won);
+}
\ No newline at end of file
@@ -0,0 +1,44 @@
+use bevy::ecs::{
+    event::EventReader,
+    query::Without,
+    schedule::NextState,
+    system::{Query, Res, ResMut}
+};
+use bevy::log;
+use bevy::render::color::Color;
+use bevy::sprite::Sprite;
+
+use crate::{Board, Coordinates, GameState};
+use crate::events::BombExplosionEvent;
+
+pub fn explosion_handler(
+    board: Res<Board>,
+    mut bomb_explosion_evr: EventReader<BombExplosionEvent>,
+    mut tiles: Query<(&Coordinates, &mut Sprite)>,
+    mut covers: Query<&mut Sprite, Without<Coordinates>>,
+    mut game_state: ResMut<NextState<GameState>>
+) {
+    let Some(explosion) = bomb_explosion_evr.iter().next() else {
+        return;
+    };
+    log::info!("Bomb exploded on {}, game lost", explosion.coordinates);
+    game_state.set(GameState::Lost);
+    // We highlight the triggering bomb, the other bombs being revealed by the game
+    if let Some((_, mut sprite)) = tiles
+        .iter_mut()
+        .find(|(coords, _)| **coords == explosion.coordinates)
+    {
+        sprite.color = Color::RED;
+    }
+    // We mark the flags placed on safe tiles as mistakes
+    for coords in board.game.wrong_flags() {
+        if let Some(mut sprite) = board
+            .covered_tiles
+            .get(&coords)
+            .and_then(|entity| covers.get_mut(*entity).ok())
+        {
+            sprite.color = Color::MAROON;
+        }
+    }
+    bomb_explosion_evr.clear();
+}
\ No newline at end of file
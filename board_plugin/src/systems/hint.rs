@@ -0,0 +1,46 @@
+use bevy::ecs::{
+    event::EventReader,
+    system::{Query, ResMut}
+};
+use bevy::log;
+use bevy::render::color::Color;
+use bevy::sprite::Sprite;
+
+use crate::{Board, COVER_COLOR};
+use crate::events::HintRequestEvent;
+
+pub fn hint_handler(
+    mut board: ResMut<Board>,
+    mut hint_request_evr: EventReader<HintRequestEvent>,
+    mut covers: Query<&mut Sprite>
+) {
+    if hint_request_evr.iter().last().is_none() {
+        return;
+    }
+    let Some((coords, mine)) = board.find_hint() else {
+        log::info!("No tile can be proven safe or mined");
+        return;
+    };
+    // Asking again for the highlighted tile is not another hint
+    if board.hint == Some(coords) {
+        return;
+    }
+    // We restore the previous highlight if its tile is still covered
+    if let Some(mut sprite) = board
+        .hint
+        .and_then(|previous| board.covered_tiles.get(&previous))
+        .and_then(|entity| covers.get_mut(*entity).ok())
+    {
+        sprite.color = COVER_COLOR;
+    }
+    board.hints_used += 1;
+    board.hint = Some(coords);
+    log::info!("Hint: {} is {}", coords, if mine { "a mine" } else { "safe" });
+    if let Some(mut sprite) = board
+        .covered_tiles
+        .get(&coords)
+        .and_then(|entity| covers.get_mut(*entity).ok())
+    {
+        sprite.color = if mine { Color::ORANGE } else { Color::CYAN };
+    }
+}
\ No newline at end of file
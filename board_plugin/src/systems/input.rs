@@ -3,28 +3,33 @@ use bevy::input::{
         MouseButton,
         MouseButtonInput
     }, 
-    ButtonState
+    ButtonState,
+    Input
 };
 use bevy::log;
 
 use bevy::ecs::{
     event::{EventReader, EventWriter},
     query::With,
-    system::{Commands, Query, Res}
+    system::{Query, Res}
 };
 use bevy::window::{PrimaryWindow, Window};
 
 use crate::Board;
-use crate::events::TileTriggerEvent;
+use crate::events::{TileChordEvent, TileMarkEvent, TileTriggerEvent};
 
 pub fn input_handling(
     window_query: Query<&Window, With<PrimaryWindow>>,
     board: Res<Board>,
+    buttons: Res<Input<MouseButton>>,
     mut button_evr: EventReader<MouseButtonInput>,
-    mut tile_trigger_ewr: EventWriter<TileTriggerEvent>
+    mut tile_trigger_ewr: EventWriter<TileTriggerEvent>,
+    mut tile_mark_ewr: EventWriter<TileMarkEvent>,
+    mut tile_chord_ewr: EventWriter<TileChordEvent>
 ) {
 
     let window = window_query.get_single().unwrap();
+    let mut chorded = false;
 
     for event in button_evr.iter() {
         if let ButtonState::Pressed = event.state {
@@ -33,6 +38,21 @@ pub fn input_handling(
                 log::info!("Mouse button pressed: {:?} at {}", event.button, pos);
                 let tile_coordinates = board.mouse_position(window, pos);
                 if let Some(coordinates) = tile_coordinates {
+                    // Pressing left and right together chords, like a middle click
+                    let chord = match event.button {
+                        MouseButton::Left => buttons.pressed(MouseButton::Right),
+                        MouseButton::Right => buttons.pressed(MouseButton::Left),
+                        MouseButton::Middle => true,
+                        _ => false
+                    };
+                    if chord {
+                        if !chorded {
+                            log::info!("Trying to chord tile on {}", coordinates);
+                            tile_chord_ewr.send(TileChordEvent{coordinates});
+                            chorded = true;
+                        }
+                        continue;
+                    }
                     match event.button {
                         MouseButton::Left => {
                             log::info!("Trying to uncover tile on {}", coordinates);
@@ -40,7 +60,7 @@ pub fn input_handling(
                         }
                         MouseButton::Right => {
                             log::info!("Trying to mark tile on {}", coordinates);
-                            // TODO: generate an event
+                            tile_mark_ewr.send(TileMarkEvent{coordinates});
                         }
                         _ => (),
                     }
@@ -0,0 +1,142 @@
+use bevy::ecs::{
+    event::EventWriter,
+    query::With,
+    system::{Local, Query, Res, ResMut}
+};
+use bevy::input::{keyboard::KeyCode, Input};
+use bevy::log;
+use bevy::render::view::Visibility;
+use bevy::transform::components::Transform;
+use minesweeper_core::TileMap;
+
+use crate::{layout, Board, Coordinates};
+use crate::components::Cursor;
+use crate::events::{TileChordEvent, TileMarkEvent, TileTriggerEvent};
+use crate::resources::KeyboardControls;
+
+/// Highest jump count, to keep moves within any board
+const MAX_JUMP_COUNT: u32 = 999;
+
+const MOVES: [(KeyCode, KeyCode, (i32, i32)); 4] = [
+    (KeyCode::Up, KeyCode::W, (0, 1)),
+    (KeyCode::Down, KeyCode::S, (0, -1)),
+    (KeyCode::Left, KeyCode::A, (-1, 0)),
+    (KeyCode::Right, KeyCode::D, (1, 0))
+];
+
+const DIGITS: [(KeyCode, KeyCode); 10] = [
+    (KeyCode::Key0, KeyCode::Numpad0),
+    (KeyCode::Key1, KeyCode::Numpad1),
+    (KeyCode::Key2, KeyCode::Numpad2),
+    (KeyCode::Key3, KeyCode::Numpad3),
+    (KeyCode::Key4, KeyCode::Numpad4),
+    (KeyCode::Key5, KeyCode::Numpad5),
+    (KeyCode::Key6, KeyCode::Numpad6),
+    (KeyCode::Key7, KeyCode::Numpad7),
+    (KeyCode::Key8, KeyCode::Numpad8),
+    (KeyCode::Key9, KeyCode::Numpad9)
+];
+
+#[allow(clippy::too_many_arguments)]
+pub fn keyboard_handling(
+    mut board: ResMut<Board>,
+    controls: Res<KeyboardControls>,
+    keys: Res<Input<KeyCode>>,
+    mut jump_count: Local<u32>,
+    mut cursor: Query<(&mut Transform, &mut Visibility), With<Cursor>>,
+    mut tile_trigger_ewr: EventWriter<TileTriggerEvent>,
+    mut tile_mark_ewr: EventWriter<TileMarkEvent>,
+    mut tile_chord_ewr: EventWriter<TileChordEvent>
+) {
+    if controls.jump_counts {
+        for (digit, (key, numpad_key)) in DIGITS.into_iter().enumerate() {
+            // A leading zero is not a count
+            if keys.any_just_pressed([key, numpad_key]) && (digit > 0 || *jump_count > 0) {
+                *jump_count = (*jump_count * 10 + digit as u32).min(MAX_JUMP_COUNT);
+            }
+        }
+    }
+    let delta = MOVES
+        .into_iter()
+        .find(|(key, alt_key, _)| keys.any_just_pressed([*key, *alt_key]))
+        .map(|(_, _, delta)| delta);
+    if let Some(delta) = delta {
+        let steps = (*jump_count).max(1) as i32;
+        *jump_count = 0;
+        let coordinates = moved_cursor(&board, delta, steps);
+        let tile_map = board.game.tile_map();
+        let position = layout::tile_center(tile_map.topology(), coordinates, board.tile_size);
+        board.cursor = Some(coordinates);
+        if let Ok((mut transform, mut visibility)) = cursor.get_single_mut() {
+            transform.translation.x = position.x;
+            transform.translation.y = position.y;
+            *visibility = Visibility::Visible;
+        }
+        return;
+    }
+    let Some(coordinates) = board.cursor else {
+        return;
+    };
+    if keys.any_just_pressed([KeyCode::Space, KeyCode::Return]) {
+        log::info!("Trying to uncover tile on {}", coordinates);
+        tile_trigger_ewr.send(TileTriggerEvent { coordinates });
+    } else if keys.just_pressed(KeyCode::F) {
+        log::info!("Trying to mark tile on {}", coordinates);
+        tile_mark_ewr.send(TileMarkEvent { coordinates });
+    } else if keys.just_pressed(KeyCode::X) {
+        log::info!("Trying to chord tile on {}", coordinates);
+        tile_chord_ewr.send(TileChordEvent { coordinates });
+    } else {
+        return;
+    }
+    *jump_count = 0;
+}
+
+/// Moves the cursor `steps` times by `(dx, dy)`, skipping holes,
+/// from the tile closest to the center of the board when not shown yet
+fn moved_cursor(board: &Board, delta: (i32, i32), steps: i32) -> Coordinates {
+    let tile_map = board.game.tile_map();
+    let Some(mut cursor) = board.cursor else {
+        let center = (tile_map.width() as i32 / 2, tile_map.height() as i32 / 2);
+        return tile_map
+            .coordinates()
+            .min_by_key(|c| (c.x as i32 - center.0).pow(2) + (c.y as i32 - center.1).pow(2))
+            .unwrap_or_default();
+    };
+    for _ in 0..steps {
+        match next_tile(tile_map, cursor, delta) {
+            Some(next) => cursor = next,
+            None => break
+        }
+    }
+    cursor
+}
+
+/// Retrieves the first existing tile from `coordinates` in the `(dx, dy)` direction.
+/// The cursor wraps around the joined edges, and stops at the others
+fn next_tile(
+    tile_map: &TileMap,
+    coordinates: Coordinates,
+    (dx, dy): (i32, i32)
+) -> Option<Coordinates> {
+    let (width, height) = (tile_map.width() as i32, tile_map.height() as i32);
+    let (mut x, mut y) = (coordinates.x as i32, coordinates.y as i32);
+    for _ in 0..width.max(height) {
+        x += dx;
+        y += dy;
+        if tile_map.wrap().horizontal() {
+            x = x.rem_euclid(width);
+        }
+        if tile_map.wrap().vertical() {
+            y = y.rem_euclid(height);
+        }
+        if x < 0 || y < 0 || x >= width || y >= height {
+            return None;
+        }
+        let next = Coordinates { x: x as u16, y: y as u16 };
+        if tile_map.exists(next) {
+            return Some(next);
+        }
+    }
+    None
+}
@@ -0,0 +1,99 @@
+use bevy::asset::{AssetServer, Handle};
+use bevy::core::Name;
+use bevy::ecs::{
+    entity::Entity,
+    event::EventReader,
+    system::{Commands, Res, ResMut}
+};
+use bevy::hierarchy::{BuildChildren, DespawnRecursiveExt};
+use bevy::log;
+use bevy::math::Vec2;
+use bevy::render::{color::Color, texture::Image};
+use bevy::sprite::{Sprite, SpriteBundle};
+use bevy::text::{Font, Text, Text2dBundle, TextAlignment, TextSection, TextStyle};
+use bevy::time::Time;
+use bevy::transform::components::Transform;
+
+use crate::{Board, Flag};
+use crate::events::TileMarkEvent;
+use crate::resources::ReplayAction;
+
+pub fn mark_tiles(
+    mut commands: Commands,
+    mut board: ResMut<Board>,
+    asset_server: Res<AssetServer>,
+    time: Res<Time>,
+    mut tile_mark_evr: EventReader<TileMarkEvent>,
+) {
+    for event in tile_mark_evr.iter() {
+        board.record(ReplayAction::Mark(event.coordinates), time.elapsed());
+        board.mark_clicks += 1;
+        if let Some((entity, count)) = board.try_toggle_mark(&event.coordinates) {
+            board.useful_clicks += 1;
+            // The previous flag, if any, is replaced
+            commands
+                .entity(entity)
+                .remove::<Flag>()
+                .despawn_descendants();
+            if count > 0 {
+                log::info!("Flagged tile {} with {} mines", event.coordinates, count);
+                flag_cover(
+                    &mut commands,
+                    entity,
+                    board.tile_size,
+                    asset_server.load("sprites/white_flag.png"),
+                    count,
+                    asset_server.load("fonts/pixeled.ttf")
+                );
+            } else {
+                log::info!("Unflagged tile {}", event.coordinates);
+            }
+        }
+    }
+}
+
+/// Adds the `Flag` component and the flag sprite to a tile cover,
+/// with the flagged mine count when above 1
+pub(crate) fn flag_cover(
+    commands: &mut Commands,
+    cover: Entity,
+    size: f32,
+    flag_image: Handle<Image>,
+    count: u8,
+    font: Handle<Font>
+) {
+    commands
+        .entity(cover)
+        .insert(Flag { count })
+        .with_children(|parent| {
+            parent.spawn(SpriteBundle {
+                sprite: Sprite {
+                    custom_size: Some(Vec2::splat(size)),
+                    ..Default::default()
+                },
+                transform: Transform::from_xyz(0., 0., 1.),
+                texture: flag_image,
+                ..Default::default()
+            })
+            .insert(Name::new("Flag"));
+            if count > 1 {
+                parent.spawn(Text2dBundle {
+                    text: Text {
+                        sections: vec![TextSection {
+                            value: count.to_string(),
+                            style: TextStyle {
+                                color: Color::BLACK,
+                                font,
+                                font_size: size / 2.,
+                            },
+                        }],
+                        alignment: TextAlignment::Center,
+                        ..Default::default()
+                    },
+                    transform: Transform::from_xyz(size / 4., -size / 4., 2.),
+                    ..Default::default()
+                })
+                .insert(Name::new("Flag Count"));
+            }
+        });
+}
\ No newline at end of file
@@ -1,2 +1,11 @@
+pub mod clock;
+pub mod completion;
+pub mod explosion;
+pub mod hint;
 pub mod input;
+pub mod keyboard;
+pub mod mark;
+pub mod replay;
+pub mod save;
+pub mod stats;
 pub mod uncover;
\ No newline at end of file
@@ -0,0 +1,97 @@
+use bevy::app::AppExit;
+use bevy::ecs::{
+    event::{EventReader, EventWriter},
+    system::{Res, ResMut}
+};
+use bevy::log;
+use bevy::time::Time;
+
+use crate::Board;
+use crate::events::{
+    BoardCompletedEvent,
+    BombExplosionEvent,
+    ReplayStepEvent,
+    TileChordEvent,
+    TileMarkEvent,
+    TileTriggerEvent
+};
+use crate::resources::{PlaybackSpeed, Replay, ReplayAction, ReplayFile, ReplayPlayback};
+
+/// Highest playback speed factor
+const MAX_PLAYBACK_FACTOR: f32 = 1000.0;
+
+/// Sends the recorded tile events once their time is reached, or one per step event
+pub fn play_replay(
+    mut playback: ResMut<ReplayPlayback>,
+    time: Res<Time>,
+    mut replay_step_evr: EventReader<ReplayStepEvent>,
+    mut tile_trigger_ewr: EventWriter<TileTriggerEvent>,
+    mut tile_mark_ewr: EventWriter<TileMarkEvent>,
+    mut tile_chord_ewr: EventWriter<TileChordEvent>
+) {
+    if playback.is_finished() {
+        return;
+    }
+    let step_count = match playback.speed {
+        PlaybackSpeed::Scaled(factor) => {
+            playback.clock += time.delta().mul_f32(playback_factor(factor));
+            // Actions are sent on separate frames to be handled in their recorded order
+            let next = &playback.replay.steps[playback.next_step];
+            usize::from(next.at <= playback.clock)
+        }
+        PlaybackSpeed::Stepwise => replay_step_evr.iter().count()
+    };
+    for _ in 0..step_count {
+        let Some(step) = playback.replay.steps.get(playback.next_step).copied() else {
+            break;
+        };
+        playback.next_step += 1;
+        match step.action {
+            ReplayAction::Trigger(coordinates) => {
+                tile_trigger_ewr.send(TileTriggerEvent { coordinates })
+            }
+            ReplayAction::Mark(coordinates) => tile_mark_ewr.send(TileMarkEvent { coordinates }),
+            ReplayAction::Chord(coordinates) => tile_chord_ewr.send(TileChordEvent { coordinates })
+        }
+    }
+    if playback.is_finished() {
+        log::info!("Replay finished");
+    }
+}
+
+/// Clamps a playback speed factor to the range `Duration` multiplication accepts.
+/// Negative or NaN factors hold the playback
+fn playback_factor(factor: f32) -> f32 {
+    if factor.is_nan() {
+        return 0.0;
+    }
+    factor.clamp(0.0, MAX_PLAYBACK_FACTOR)
+}
+
+/// Player input is ignored while a replay is being played back
+pub fn no_playback(playback: Option<Res<ReplayPlayback>>) -> bool {
+    playback.is_none_or(|p| p.is_finished())
+}
+
+/// Writes the replay of the game when it ends or the app exits, unless the game is itself a replay
+pub fn save_replay(
+    board: Res<Board>,
+    replay_file: Option<Res<ReplayFile>>,
+    playback: Option<Res<ReplayPlayback>>,
+    mut board_completed_evr: EventReader<BoardCompletedEvent>,
+    mut bomb_explosion_evr: EventReader<BombExplosionEvent>,
+    mut app_exit_evr: EventReader<AppExit>
+) {
+    let game_over = board_completed_evr.iter().count() + bomb_explosion_evr.iter().count() > 0;
+    let exiting = app_exit_evr.iter().count() > 0;
+    let Some(replay_file) = replay_file else {
+        return;
+    };
+    if !(game_over || exiting) || playback.is_some() || board.recording.is_empty() {
+        return;
+    }
+    match Replay::from_board(&board).save(&replay_file.0) {
+        Ok(()) => log::info!("Replay saved to {}", replay_file.0.display()),
+        Err(e) => log::error!("Failed to save replay: {}", e)
+    }
+}
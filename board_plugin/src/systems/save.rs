@@ -0,0 +1,40 @@
+use std::fs;
+use std::io::ErrorKind;
+use bevy::app::AppExit;
+use bevy::ecs::{
+    event::EventReader,
+    system::Res
+};
+use bevy::log;
+use bevy::time::Time;
+use minesweeper_core::GameStatus;
+
+use crate::Board;
+use crate::resources::{SaveFile, SavedGame};
+
+/// Saves the game in progress on exit, or removes the save of a finished game
+pub fn autosave(
+    board: Option<Res<Board>>,
+    save_file: Option<Res<SaveFile>>,
+    time: Res<Time>,
+    mut app_exit_evr: EventReader<AppExit>
+) {
+    if app_exit_evr.iter().last().is_none() {
+        return;
+    }
+    let (Some(board), Some(save_file)) = (board, save_file) else {
+        return;
+    };
+    if board.game.status() != GameStatus::Playing {
+        if let Err(e) = fs::remove_file(&save_file.0) {
+            if e.kind() != ErrorKind::NotFound {
+                log::error!("Failed to remove save file: {}", e);
+            }
+        }
+        return;
+    }
+    match SavedGame::from_board(&board, time.elapsed()).save(&save_file.0) {
+        Ok(()) => log::info!("Game saved to {}", save_file.0.display()),
+        Err(e) => log::error!("Failed to save game: {}", e)
+    }
+}
\ No newline at end of file
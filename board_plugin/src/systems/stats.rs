@@ -0,0 +1,53 @@
+use bevy::ecs::{
+    event::EventReader,
+    system::Res
+};
+use bevy::log;
+use bevy::time::Time;
+
+use crate::Board;
+use crate::events::{BoardCompletedEvent, BombExplosionEvent};
+use crate::resources::{ReplayPlayback, Stats, StatsBucket, StatsFile};
+
+/// Counts the finished game in the statistics file, unless the game is a replay
+pub fn record_stats(
+    board: Res<Board>,
+    stats_file: Option<Res<StatsFile>>,
+    playback: Option<Res<ReplayPlayback>>,
+    time: Res<Time>,
+    mut board_completed_evr: EventReader<BoardCompletedEvent>,
+    mut bomb_explosion_evr: EventReader<BombExplosionEvent>
+) {
+    let won = board_completed_evr.iter().last().map(|e| e.elapsed);
+    let lost = bomb_explosion_evr.iter().count() > 0;
+    let Some(stats_file) = stats_file else {
+        return;
+    };
+    if (won.is_none() && !lost) || playback.is_some() {
+        return;
+    }
+    let mut stats = match Stats::load(&stats_file.0) {
+        Ok(stats) => stats,
+        Err(e) => {
+            log::error!("Failed to load statistics: {}", e);
+            return;
+        }
+    };
+    let bucket = StatsBucket::of(&board.options);
+    let elapsed = won.unwrap_or_else(|| board.elapsed(time.elapsed()));
+    let game_stats = stats.record(bucket, won.is_some(), elapsed);
+    log::info!(
+        "{:?}: {} wins out of {} games ({:.0}%), streak {} (best {}), best time {:?}, average {:?}",
+        bucket,
+        game_stats.wins,
+        game_stats.games_played,
+        game_stats.win_rate() * 100.0,
+        game_stats.current_streak,
+        game_stats.best_streak,
+        game_stats.best_time,
+        game_stats.average_time()
+    );
+    if let Err(e) = stats.save(&stats_file.0) {
+        log::error!("Failed to save statistics: {}", e);
+    }
+}
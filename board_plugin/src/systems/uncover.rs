@@ -1,24 +1,76 @@
 use bevy::ecs::{
     entity::Entity,
-    event::EventReader,
+    event::{EventReader, EventWriter},
     query::With,
     system::{Commands, Query, Res, ResMut}
 };
 use bevy::hierarchy::{DespawnRecursiveExt, Parent};
 use bevy::log;
+use bevy::time::Time;
+use minesweeper_core::Reveal;
 
-use crate::{Board, Bomb, BombNeighbor, Coordinates, Uncover};
-use crate::events::TileTriggerEvent;
+use crate::{Board, Coordinates, Uncover};
+use crate::resources::ReplayAction;
+use crate::events::{BombExplosionEvent, TileChordEvent, TileTriggerEvent};
 
 pub fn trigger_event_handler(
     mut commands: Commands,
-    board: Res<Board>,
+    mut board: ResMut<Board>,
+    time: Res<Time>,
     mut tile_trigger_evr: EventReader<TileTriggerEvent>,
+    mut bomb_explosion_ewr: EventWriter<BombExplosionEvent>,
 ) {
+    // Actions are recorded as they are handled, in the order they are applied to the game
     for trigger_event in tile_trigger_evr.iter() {
-        if let Some(entity) = board.tile_to_uncover(&trigger_event.coordinates) {
-            commands.entity(*entity).insert(Uncover {});
+        board.record(ReplayAction::Trigger(trigger_event.coordinates), time.elapsed());
+        board.uncover_clicks += 1;
+        if board.game.is_flagged(trigger_event.coordinates) {
+            log::info!("Tile {} is flagged, ignoring", trigger_event.coordinates);
+            continue;
+        }
+        let reveal = board.game.reveal(trigger_event.coordinates);
+        if !reveal.uncovered.is_empty() {
+            board.useful_clicks += 1;
+            // The clock starts on the first reveal
+            board.start_clock(time.elapsed());
+        }
+        apply_reveal(&mut commands, &board, &reveal, &mut bomb_explosion_ewr);
+    }
+}
+
+pub fn chord_event_handler(
+    mut commands: Commands,
+    mut board: ResMut<Board>,
+    time: Res<Time>,
+    mut tile_chord_evr: EventReader<TileChordEvent>,
+    mut bomb_explosion_ewr: EventWriter<BombExplosionEvent>,
+) {
+    for chord_event in tile_chord_evr.iter() {
+        board.record(ReplayAction::Chord(chord_event.coordinates), time.elapsed());
+        board.chord_clicks += 1;
+        let reveal = board.game.chord(chord_event.coordinates);
+        if reveal.uncovered.is_empty() {
+            log::info!("Tile {} cannot be chorded, ignoring", chord_event.coordinates);
+            continue;
         }
+        board.useful_clicks += 1;
+        apply_reveal(&mut commands, &board, &reveal, &mut bomb_explosion_ewr);
+    }
+}
+
+/// Marks the covers of the revealed tiles to be uncovered and reports explosions
+fn apply_reveal(
+    commands: &mut Commands,
+    board: &Board,
+    reveal: &Reveal,
+    bomb_explosion_ewr: &mut EventWriter<BombExplosionEvent>
+) {
+    for entity in board.revealed_covers(reveal) {
+        commands.entity(entity).insert(Uncover {});
+    }
+    if let Some(coordinates) = reveal.exploded {
+        log::info!("Boom !");
+        bomb_explosion_ewr.send(BombExplosionEvent { coordinates });
     }
 }
 
@@ -26,7 +78,7 @@ pub fn uncover_tiles(
     mut commands: Commands,
     mut board: ResMut<Board>,
     children: Query<(Entity, &Parent), With<Uncover>>,
-    parents: Query<(&Coordinates, Option<&Bomb>, Option<&BombNeighbor>)>,
+    parents: Query<&Coordinates>,
 ) {
     // We iterate through tile covers to uncover
     for (entity, parent) in children.iter() {
@@ -34,7 +86,7 @@ pub fn uncover_tiles(
         commands
             .entity(entity)
             .despawn_recursive();
-        let (coords, bomb, bomb_counter) = match parents.get(parent.get()) {
+        let coords = match parents.get(parent.get()) {
             Ok(v) => v,
             Err(e) => {
                 log::error!("{}", e);
@@ -46,17 +98,5 @@ pub fn uncover_tiles(
             None => log::warn!("Tried to uncover an already uncovered tile"),
             Some(e) => log::debug!("Uncovered tile {} (entity: {:?})", coords, e),
         }
-        if bomb.is_some() {
-            log::info!("Boom !");
-            // TODO: Add explosion event
-        }
-        // If the tile is empty..
-        else if bomb_counter.is_none() {
-            // .. We propagate the uncovering by adding the `Uncover` component to adjacent tiles
-            // which will then be removed next frame
-            for entity in board.adjacent_covered_tiles(*coords) {
-                commands.entity(entity).insert(Uncover {});
-            }
-        }
     }
-}
+}
\ No newline at end of file
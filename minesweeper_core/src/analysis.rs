@@ -0,0 +1,117 @@
+use std::collections::HashSet;
+
+use crate::{Coordinates, Tile, TileMap};
+
+/// Clearing difficulty of a generated map
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct BoardAnalysis {
+    /// Bechtel's Board Benchmark Value: the minimum number of clicks clearing the map,
+    /// one per opening and one per numbered tile outside of every opening
+    pub three_bv: u32,
+    /// Tiles uncovered by a click on each opening: its empty tiles and their numbered border
+    pub openings: Vec<Vec<Coordinates>>,
+    /// Groups of adjacent numbered tiles bordering no opening, every tile needing its own click
+    pub islands: Vec<Vec<Coordinates>>
+}
+
+impl BoardAnalysis {
+    /// Number of openings
+    pub fn opening_count(&self) -> usize {
+        self.openings.len()
+    }
+
+    /// Number of tiles uncovered by each opening
+    pub fn opening_sizes(&self) -> impl Iterator<Item = usize> + '_ {
+        self.openings.iter().map(Vec::len)
+    }
+
+    /// Number of tiles of each island
+    pub fn island_sizes(&self) -> impl Iterator<Item = usize> + '_ {
+        self.islands.iter().map(Vec::len)
+    }
+}
+
+impl TileMap {
+    /// Computes the openings, islands and 3BV of the map, which must have its bombs placed
+    pub fn analyze(&self) -> BoardAnalysis {
+        let mut visited: HashSet<Coordinates> = HashSet::new();
+        let mut openings = Vec::new();
+        for coords in self.coordinates() {
+            if self.tile_at(coords) != Some(&Tile::Empty) || visited.contains(&coords) {
+                continue;
+            }
+            // We cascade like a click would, stopping at the numbered tiles,
+            // which may also border other openings
+            let mut opening = vec![coords];
+            let mut stack = vec![coords];
+            let mut reached = HashSet::from([coords]);
+            while let Some(coords) = stack.pop() {
+                for neighbor in self.safe_square_at(coords) {
+                    if !reached.insert(neighbor) {
+                        continue;
+                    }
+                    opening.push(neighbor);
+                    if self.tile_at(neighbor) == Some(&Tile::Empty) {
+                        stack.push(neighbor);
+                    }
+                }
+            }
+            visited.extend(opening.iter().copied());
+            openings.push(opening);
+        }
+        // Remaining numbered tiles are grouped by adjacency
+        let mut islands = Vec::new();
+        for coords in self.coordinates() {
+            if !matches!(self.tile_at(coords), Some(Tile::BombNeighbor(_))) || visited.contains(&coords) {
+                continue;
+            }
+            let mut island = Vec::new();
+            let mut stack = vec![coords];
+            visited.insert(coords);
+            while let Some(coords) = stack.pop() {
+                island.push(coords);
+                for neighbor in self.safe_square_at(coords) {
+                    if matches!(self.tile_at(neighbor), Some(Tile::BombNeighbor(_)))
+                        && visited.insert(neighbor)
+                    {
+                        stack.push(neighbor);
+                    }
+                }
+            }
+            islands.push(island);
+        }
+        let island_tiles: usize = islands.iter().map(Vec::len).sum();
+        BoardAnalysis {
+            three_bv: (openings.len() + island_tiles) as u32,
+            openings,
+            islands
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn three_bv_of_a_small_board() {
+        let tile_map = TileMap::from_text("5x3\n*....\n.....\n...*.\n").unwrap();
+        let analysis = tile_map.analyze();
+        assert_eq!(analysis.three_bv, 3);
+        assert_eq!(analysis.opening_count(), 2);
+        // Both openings share the numbered tiles between them
+        let mut sizes: Vec<usize> = analysis.opening_sizes().collect();
+        sizes.sort();
+        assert_eq!(sizes, [6, 8]);
+        assert_eq!(analysis.islands, [vec![Coordinates { x: 4, y: 0 }]]);
+    }
+
+    #[test]
+    fn board_without_openings() {
+        let tile_map = TileMap::from_text("3x1\n.*.\n").unwrap();
+        let analysis = tile_map.analyze();
+        assert_eq!(analysis.three_bv, 2);
+        assert_eq!(analysis.opening_count(), 0);
+        assert_eq!(analysis.island_sizes().collect::<Vec<_>>(), [1, 1]);
+    }
+}
@@ -1,8 +1,9 @@
 use std::fmt::{self, Display, Formatter};
 use std::ops::{Add, Sub};
-use bevy::prelude::Component;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component)]
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "bevy", derive(bevy_ecs::component::Component))]
 pub struct Coordinates {
     pub x: u16,
     pub y: u16
@@ -0,0 +1,66 @@
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+/// Errors raised when a board cannot be generated
+#[derive(Debug, Clone, PartialEq)]
+pub enum BoardError {
+    /// The tile map has no tiles
+    EmptyMap,
+    /// The map is wider or taller than `TileMap::MAX_SIZE`
+    MapTooLarge { width: u16, height: u16 },
+    /// The bomb density is not strictly between 0 and 1
+    InvalidDensity(f32),
+    /// Hex rows cannot line up across the top and bottom edges of an odd height map
+    OddHexTorus,
+    /// Bomb tiles must hold between 1 and 3 mines
+    InvalidMaxMines(u8),
+    /// The board mask could not be loaded
+    InvalidMask(String),
+    /// Tiles have no neighbors, or too many mines around for their count to fit in a tile
+    InvalidNeighborhood { size: usize, max_mines: u8 },
+    /// A text board is malformed at the given 1-based line and column
+    InvalidText { line: usize, column: usize, reason: String },
+    /// Not enough free tiles to place every bomb
+    TooManyBombs { bomb_count: u16, available: usize },
+    /// No candidate layout could be solved without guessing
+    NoGuessNotFound { attempts: u32 }
+}
+
+impl Display for BoardError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyMap => write!(f, "the tile map has no tiles"),
+            Self::MapTooLarge { width, height } => write!(
+                f,
+                "{}x{} map exceeds the maximum size of {} tiles per side",
+                width,
+                height,
+                crate::TileMap::MAX_SIZE
+            ),
+            Self::InvalidDensity(d) => write!(f, "bomb density {} is not between 0 and 1", d),
+            Self::OddHexTorus => write!(f, "hex maps need an even height to wrap vertically"),
+            Self::InvalidMaxMines(m) => write!(f, "{} mines per tile is not between 1 and 3", m),
+            Self::InvalidMask(reason) => write!(f, "invalid board mask: {}", reason),
+            Self::InvalidNeighborhood { size, max_mines } => write!(
+                f,
+                "neighborhood of {} tiles with up to {} mines each cannot count 1 to 255 mines",
+                size, max_mines
+            ),
+            Self::InvalidText { line, column, reason } => {
+                write!(f, "invalid text board at line {}, column {}: {}", line, column, reason)
+            }
+            Self::TooManyBombs { bomb_count, available } => write!(
+                f,
+                "cannot place {} bombs on {} available tiles",
+                bomb_count, available
+            ),
+            Self::NoGuessNotFound { attempts } => write!(
+                f,
+                "no board solvable without guessing found in {} attempts",
+                attempts
+            )
+        }
+    }
+}
+
+impl Error for BoardError {}
\ No newline at end of file
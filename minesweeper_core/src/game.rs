@@ -0,0 +1,397 @@
+use std::collections::{HashMap, HashSet};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    no_guess, BoardError, Coordinates, NoGuess, NoGuessFallback, Tile, TileMap, Topology
+};
+
+/// First click protection options
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum SafeFirstClick {
+    /// Bombs are placed when the board is generated
+    #[default]
+    Disabled,
+    /// Bombs are placed on first click, never on the clicked tile
+    Tile,
+    /// Bombs are placed on first click, never on the clicked tile or its neighbors
+    Area
+}
+
+/// Progress of a game
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum GameStatus {
+    /// Tiles can still be revealed and flagged
+    Playing,
+    /// Every safe tile was revealed
+    Won,
+    /// A bomb was revealed
+    Lost { exploded: Coordinates }
+}
+
+/// Tiles uncovered by a single action
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Reveal {
+    /// Uncovered tiles, including the cascade and the bombs shown on loss
+    pub uncovered: Vec<Coordinates>,
+    /// The bomb that exploded, if any
+    pub exploded: Option<Coordinates>
+}
+
+/// Bombs waiting for the first reveal to be placed
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+struct PendingBombs {
+    bomb_count: u16,
+    seed: u64,
+    safe_first_click: SafeFirstClick,
+    no_guess: Option<NoGuess>
+}
+
+/// Minesweeper rules over a `TileMap`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Game {
+    tile_map: TileMap,
+    covered: HashSet<Coordinates>,
+    /// Flagged tiles with the number of mines the player put on them
+    flagged: HashMap<Coordinates, u8>,
+    status: GameStatus,
+    pending: Option<PendingBombs>
+}
+
+impl Game {
+    /// Starts a game on an already generated map, every tile covered
+    pub fn new(tile_map: TileMap) -> Self {
+        let covered = tile_map.coordinates().collect();
+        Self {
+            tile_map,
+            covered,
+            flagged: HashMap::new(),
+            status: GameStatus::Playing,
+            pending: None
+        }
+    }
+
+    /// Starts a game on the empty `tile_map`, placing the bombs right away or on the first reveal.
+    /// No-guess boards placed right away are solvable from their first empty tile.
+    pub fn generate(
+        mut tile_map: TileMap,
+        bomb_count: u16,
+        seed: u64,
+        safe_first_click: SafeFirstClick,
+        no_guess: Option<NoGuess>
+    ) -> Result<Self, BoardError> {
+        Self::validate(&tile_map, bomb_count)?;
+        // The solver cannot clear maps with multi-mine tiles
+        let no_guess = no_guess.filter(|_| tile_map.max_mines() == 1);
+        let pending = match (safe_first_click, no_guess) {
+            (SafeFirstClick::Disabled, None) => {
+                tile_map.set_bombs(bomb_count, seed)?;
+                None
+            }
+            (SafeFirstClick::Disabled, Some(no_guess)) => {
+                tile_map = no_guess::generate(&tile_map, bomb_count, &[], None, seed, no_guess)?;
+                None
+            }
+            _ => Some(PendingBombs { bomb_count, seed, safe_first_click, no_guess })
+        };
+        Ok(Self {
+            pending,
+            ..Self::new(tile_map)
+        })
+    }
+
+    /// Checks that a game of `bomb_count` bombs can be played on `tile_map`
+    pub fn validate(tile_map: &TileMap, bomb_count: u16) -> Result<(), BoardError> {
+        let tile_count = tile_map.tile_count();
+        if tile_count == 0 {
+            return Err(BoardError::EmptyMap);
+        }
+        if tile_map.width() > TileMap::MAX_SIZE || tile_map.height() > TileMap::MAX_SIZE {
+            return Err(BoardError::MapTooLarge {
+                width: tile_map.width(),
+                height: tile_map.height()
+            });
+        }
+        if tile_map.topology() == Topology::Hex
+            && tile_map.wrap().vertical()
+            && tile_map.height() % 2 == 1
+        {
+            return Err(BoardError::OddHexTorus);
+        }
+        if !(1..=3).contains(&tile_map.max_mines()) {
+            return Err(BoardError::InvalidMaxMines(tile_map.max_mines()));
+        }
+        if tile_map.topology() == Topology::Square {
+            tile_map.neighborhood().validate(tile_map.max_mines())?;
+        }
+        // At least one tile must be safe
+        if bomb_count as usize >= tile_count {
+            return Err(BoardError::TooManyBombs { bomb_count, available: tile_count - 1 });
+        }
+        Ok(())
+    }
+
+    /// Uncovers the tile at `coords`, cascading through empty tiles
+    pub fn reveal(&mut self, coords: Coordinates) -> Reveal {
+        let mut reveal = Reveal::default();
+        if !self.can_reveal(coords) {
+            return reveal;
+        }
+        self.place_pending_bombs(coords);
+        self.uncover_from(coords, &mut reveal);
+        self.update_status(&mut reveal);
+        reveal
+    }
+
+    /// Uncovers the unflagged neighbors of a numbered tile once enough neighbors are flagged.
+    /// A misplaced flag makes the chord reveal a bomb.
+    pub fn chord(&mut self, coords: Coordinates) -> Reveal {
+        let mut reveal = Reveal::default();
+        if self.status != GameStatus::Playing || self.is_covered(coords) {
+            return reveal;
+        }
+        let count = match self.tile_map.tile_at(coords) {
+            Some(Tile::BombNeighbor(v)) => *v as usize,
+            _ => return reveal
+        };
+        if self.adjacent_flag_count(coords) != count {
+            return reveal;
+        }
+        let neighbors: Vec<Coordinates> = self.tile_map.safe_square_at(coords).collect();
+        for neighbor in neighbors {
+            if self.can_reveal(neighbor) {
+                self.uncover_from(neighbor, &mut reveal);
+            }
+        }
+        self.update_status(&mut reveal);
+        reveal
+    }
+
+    /// Cycles the flag on a covered tile through the possible mine counts and back to unflagged,
+    /// returning the new flag count, 0 when unflagged
+    pub fn flag(&mut self, coords: Coordinates) -> Option<u8> {
+        if self.status != GameStatus::Playing || !self.is_covered(coords) {
+            return None;
+        }
+        let count = (self.flag_count(coords) + 1) % (self.tile_map.max_mines() + 1);
+        if count == 0 {
+            self.flagged.remove(&coords);
+        } else {
+            self.flagged.insert(coords, count);
+        }
+        Some(count)
+    }
+
+    /// Sums the flag counts around `coords`
+    pub fn adjacent_flag_count(&self, coords: Coordinates) -> usize {
+        self.tile_map
+            .safe_square_at(coords)
+            .map(|c| self.flag_count(c) as usize)
+            .sum()
+    }
+
+    /// Retrieves the flags placed on safe tiles, or with the wrong mine count
+    pub fn wrong_flags(&self) -> impl Iterator<Item = Coordinates> + '_ {
+        self.flagged
+            .iter()
+            .filter(|(c, count)| self.tile_map.tile_at(**c).map_or(0, Tile::mine_count) != **count)
+            .map(|(c, _)| *c)
+    }
+
+    /// Is the tile at `coords` covered?
+    pub fn is_covered(&self, coords: Coordinates) -> bool {
+        self.covered.contains(&coords)
+    }
+
+    /// Is the tile at `coords` flagged?
+    pub fn is_flagged(&self, coords: Coordinates) -> bool {
+        self.flagged.contains_key(&coords)
+    }
+
+    /// Number of mines flagged on the tile at `coords`, 0 when unflagged
+    pub fn flag_count(&self, coords: Coordinates) -> u8 {
+        self.flagged.get(&coords).copied().unwrap_or(0)
+    }
+
+    /// Are the bombs placed?
+    pub fn is_generated(&self) -> bool {
+        self.pending.is_none()
+    }
+
+    /// Getter for the covered tiles
+    pub fn covered_tiles(&self) -> &HashSet<Coordinates> {
+        &self.covered
+    }
+
+    /// Iterates over the flagged tiles
+    pub fn flagged_tiles(&self) -> impl Iterator<Item = Coordinates> + '_ {
+        self.flagged.keys().copied()
+    }
+
+    /// Getter for `status`
+    pub fn status(&self) -> GameStatus {
+        self.status
+    }
+
+    /// Getter for `tile_map`
+    pub fn tile_map(&self) -> &TileMap {
+        &self.tile_map
+    }
+
+    fn can_reveal(&self, coords: Coordinates) -> bool {
+        self.status == GameStatus::Playing && self.is_covered(coords) && !self.is_flagged(coords)
+    }
+
+    fn place_pending_bombs(&mut self, coords: Coordinates) {
+        let Some(pending) = self.pending.take() else {
+            return;
+        };
+        let mut excluded = vec![coords];
+        if pending.safe_first_click == SafeFirstClick::Area {
+            let area: Vec<Coordinates> = self.tile_map.safe_square_at(coords).collect();
+            // We only protect the neighbors when the remaining tiles can hold every bomb
+            let tile_count = self.tile_map.tile_count();
+            if tile_count > pending.bomb_count as usize + area.len() {
+                excluded.extend(area);
+            }
+        }
+        if let Some(no_guess) = pending.no_guess {
+            let no_guess = NoGuess {
+                fallback: NoGuessFallback::BestAttempt,
+                ..no_guess
+            };
+            if let Ok(tile_map) = no_guess::generate(
+                &self.tile_map,
+                pending.bomb_count,
+                &excluded,
+                Some(coords),
+                pending.seed,
+                no_guess
+            ) {
+                self.tile_map = tile_map;
+                return;
+            }
+        }
+        // The bomb count was checked against the map size on generation
+        self.tile_map
+            .set_bombs_excluding(pending.bomb_count, &excluded, pending.seed)
+            .expect("first click exclusion leaves room for every bomb");
+    }
+
+    fn uncover_from(&mut self, coords: Coordinates, reveal: &mut Reveal) {
+        let mut stack = vec![coords];
+        while let Some(coords) = stack.pop() {
+            if self.is_flagged(coords) || !self.covered.remove(&coords) {
+                continue;
+            }
+            reveal.uncovered.push(coords);
+            match self.tile_map.tile_at(coords) {
+                Some(Tile::Bomb(_)) => {
+                    reveal.exploded.get_or_insert(coords);
+                }
+                // We propagate the uncovering to the neighbors of empty tiles
+                Some(Tile::Empty) => stack.extend(
+                    self.tile_map
+                        .safe_square_at(coords)
+                        .filter(|c| self.is_covered(*c))
+                ),
+                _ => ()
+            }
+        }
+    }
+
+    fn update_status(&mut self, reveal: &mut Reveal) {
+        if let Some(exploded) = reveal.exploded {
+            self.status = GameStatus::Lost { exploded };
+            // Every remaining unflagged bomb is shown
+            let bombs: Vec<Coordinates> = self
+                .covered
+                .iter()
+                .copied()
+                .filter(|c| self.tile_map.is_bomb_at(*c) && !self.is_flagged(*c))
+                .collect();
+            for coords in bombs {
+                self.covered.remove(&coords);
+                reveal.uncovered.push(coords);
+            }
+        } else if self.covered.len() == self.tile_map.bomb_count() as usize
+            && self.covered.iter().all(|c| self.tile_map.is_bomb_at(*c))
+        {
+            self.status = GameStatus::Won;
+            // The remaining mines are flagged
+            self.flagged = self
+                .covered
+                .iter()
+                .map(|c| (*c, self.tile_map.tile_at(*c).map_or(0, Tile::mine_count)))
+                .collect();
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(text: &str) -> Game {
+        Game::new(TileMap::from_text(text).unwrap())
+    }
+
+    #[test]
+    fn cascade_stops_at_numbers() {
+        let mut game = game("5x3\n..*..\n..*..\n..*..\n");
+        let reveal = game.reveal(Coordinates { x: 0, y: 0 });
+        let mut uncovered = reveal.uncovered.clone();
+        uncovered.sort_by_key(|c| (c.x, c.y));
+        let expected: Vec<Coordinates> =
+            (0..2).flat_map(|x| (0..3).map(move |y| Coordinates { x, y })).collect();
+        assert_eq!(uncovered, expected);
+        assert_eq!(reveal.exploded, None);
+        assert_eq!(game.status(), GameStatus::Playing);
+    }
+
+    #[test]
+    fn cascade_stops_at_flags() {
+        let mut game = game("4x1\n...*\n");
+        game.flag(Coordinates { x: 1, y: 0 });
+        let reveal = game.reveal(Coordinates { x: 0, y: 0 });
+        assert_eq!(reveal.uncovered, [Coordinates { x: 0, y: 0 }]);
+        assert!(game.is_covered(Coordinates { x: 1, y: 0 }));
+        assert!(game.is_covered(Coordinates { x: 2, y: 0 }));
+    }
+
+    #[test]
+    fn chord_with_a_wrong_flag_loses() {
+        let mut game = game("3x2\n*.*\n...\n");
+        game.reveal(Coordinates { x: 0, y: 0 });
+        // The 1 is satisfied by a flag on a safe tile
+        game.flag(Coordinates { x: 1, y: 0 });
+        let reveal = game.chord(Coordinates { x: 0, y: 0 });
+        assert_eq!(reveal.exploded, Some(Coordinates { x: 0, y: 1 }));
+        assert_eq!(game.status(), GameStatus::Lost { exploded: Coordinates { x: 0, y: 1 } });
+        assert_eq!(game.wrong_flags().collect::<Vec<_>>(), [Coordinates { x: 1, y: 0 }]);
+    }
+
+    #[test]
+    fn safe_area_first_click_opens_a_cascade() {
+        let start = Coordinates { x: 4, y: 4 };
+        for seed in 0..20 {
+            let mut game =
+                Game::generate(TileMap::empty(9, 9), 10, seed, SafeFirstClick::Area, None).unwrap();
+            assert!(!game.is_generated());
+            let reveal = game.reveal(start);
+            assert!(game.is_generated());
+            assert_eq!(game.tile_map().tile_at(start), Some(&Tile::Empty));
+            assert!(reveal.uncovered.len() > 1);
+            assert_eq!(reveal.exploded, None);
+        }
+    }
+
+    #[test]
+    fn revealing_the_last_safe_tile_wins() {
+        let mut game = game("3x1\n.*.\n");
+        game.reveal(Coordinates { x: 0, y: 0 });
+        assert_eq!(game.status(), GameStatus::Playing);
+        game.reveal(Coordinates { x: 2, y: 0 });
+        assert_eq!(game.status(), GameStatus::Won);
+        // The remaining mine is flagged
+        assert_eq!(game.flagged_tiles().collect::<Vec<_>>(), [Coordinates { x: 1, y: 0 }]);
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+
+use crate::{Coordinates, Game, Tile, TileMap};
+
+/// What the player can see of a tile
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Cell {
+    /// Covered tile
+    Covered,
+    /// Covered tile flagged by the player, who may be wrong
+    Flagged,
+    /// Uncovered bomb
+    Mine,
+    /// Uncovered safe tile with its bomb neighbor count
+    Revealed(u8)
+}
+
+/// Visible state of a board, without the hidden tile values
+#[derive(Debug, Clone)]
+pub struct Knowledge<'a> {
+    /// Map used for its geometry only
+    tile_map: &'a TileMap,
+    cells: HashMap<Coordinates, Cell>,
+    bomb_count: Option<u16>
+}
+
+impl<'a> Knowledge<'a> {
+    /// Builds the knowledge from the visible `cells` of a map, and the total bomb count when known
+    pub fn new(
+        tile_map: &'a TileMap,
+        cells: HashMap<Coordinates, Cell>,
+        bomb_count: Option<u16>
+    ) -> Self {
+        Self {
+            tile_map,
+            cells,
+            bomb_count
+        }
+    }
+
+    /// Retrieves the visible state of the tile at `coords`, if in the map
+    pub fn cell(&self, coords: Coordinates) -> Option<Cell> {
+        self.cells.get(&coords).copied()
+    }
+
+    /// Iterates over every tile and its visible state
+    pub fn cells(&self) -> impl Iterator<Item = (Coordinates, Cell)> + '_ {
+        self.cells.iter().map(|(c, cell)| (*c, *cell))
+    }
+
+    /// Iterates over the in-map neighbors of `coords`
+    pub fn neighbors(&self, coords: Coordinates) -> impl Iterator<Item = Coordinates> + '_ {
+        self.tile_map
+            .safe_square_at(coords)
+            .filter(|c| self.cells.contains_key(c))
+    }
+
+    /// Maximum number of mines on a bomb tile
+    pub fn max_mines(&self) -> u8 {
+        self.tile_map.max_mines()
+    }
+
+    /// Getter for the total bomb count, when known
+    pub fn bomb_count(&self) -> Option<u16> {
+        self.bomb_count
+    }
+}
+
+impl Game {
+    /// Retrieves the visible state of the game
+    pub fn knowledge(&self) -> Knowledge<'_> {
+        let tile_map = self.tile_map();
+        let cells = tile_map
+            .coordinates()
+            .map(|c| {
+                let cell = if self.is_flagged(c) {
+                    Cell::Flagged
+                } else if self.is_covered(c) {
+                    Cell::Covered
+                } else {
+                    match tile_map.tile_at(c) {
+                        Some(Tile::Bomb(_)) => Cell::Mine,
+                        Some(Tile::BombNeighbor(v)) => Cell::Revealed(*v),
+                        _ => Cell::Revealed(0)
+                    }
+                };
+                (c, cell)
+            })
+            .collect();
+        let bomb_count = self.is_generated().then(|| tile_map.bomb_count());
+        Knowledge::new(tile_map, cells, bomb_count)
+    }
+}
\ No newline at end of file
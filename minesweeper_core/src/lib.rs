@@ -0,0 +1,28 @@
+pub use analysis::BoardAnalysis;
+pub use coordinates::Coordinates;
+pub use error::BoardError;
+pub use game::{Game, GameStatus, Reveal, SafeFirstClick};
+pub use knowledge::{Cell, Knowledge};
+pub use mask::Mask;
+pub use neighborhood::Neighborhood;
+pub use no_guess::{NoGuess, NoGuessFallback};
+pub use probability::Probabilities;
+pub use solver::{Solution, Solver};
+pub use tile::Tile;
+pub use tile_map::TileMap;
+pub use topology::{Topology, Wrap};
+
+mod analysis;
+mod coordinates;
+mod error;
+mod game;
+mod knowledge;
+mod mask;
+mod neighborhood;
+mod no_guess;
+mod probability;
+mod solver;
+mod text;
+mod tile;
+mod tile_map;
+mod topology;
\ No newline at end of file
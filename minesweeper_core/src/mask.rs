@@ -0,0 +1,128 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{BoardError, Coordinates, TileMap};
+
+/// Tiles that exist on a map, for boards that are not rectangles
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Mask {
+    width: u16,
+    height: u16,
+    /// Row-major existence flags, from the bottom row
+    cells: Vec<bool>
+}
+
+impl Mask {
+    /// Builds a `width` by `height` mask from an existence predicate
+    pub fn from_fn(width: u16, height: u16, exists: impl Fn(Coordinates) -> bool) -> Self {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| Coordinates { x, y }))
+            .map(exists)
+            .collect();
+        Self { width, height, cells }
+    }
+
+    /// Parses an ASCII layout, top line first, where `#` is a tile and `.` or a space is a hole.
+    /// Short lines are padded with holes
+    pub fn from_ascii(layout: &str) -> Result<Self, BoardError> {
+        let lines: Vec<&str> = layout.trim_end_matches(['\n', '\r']).lines().collect();
+        let width = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+        let mut rows = Vec::with_capacity(lines.len());
+        for (line_number, line) in lines.iter().enumerate() {
+            let mut row = vec![false; width];
+            for (column, c) in line.chars().enumerate() {
+                row[column] = match c {
+                    '#' => true,
+                    '.' | ' ' => false,
+                    _ => {
+                        return Err(BoardError::InvalidMask(format!(
+                            "unexpected '{}' at line {}, column {}",
+                            c,
+                            line_number + 1,
+                            column + 1
+                        )))
+                    }
+                };
+            }
+            rows.push(row);
+        }
+        let max_size = TileMap::MAX_SIZE as usize;
+        if width > max_size || rows.len() > max_size {
+            return Err(BoardError::InvalidMask(format!(
+                "{}x{} layout exceeds {} tiles per side",
+                width,
+                rows.len(),
+                max_size
+            )));
+        }
+        let (width, height) = (width as u16, rows.len() as u16);
+        // The first line is the top row
+        let cells = rows.into_iter().rev().flatten().collect();
+        Ok(Self { width, height, cells })
+    }
+
+    /// Does the tile at `coordinates` exist?
+    pub fn contains(&self, coordinates: Coordinates) -> bool {
+        coordinates.x < self.width
+            && coordinates.y < self.height
+            && self.cells[coordinates.y as usize * self.width as usize + coordinates.x as usize]
+    }
+
+    /// Number of existing tiles
+    pub fn tile_count(&self) -> usize {
+        self.cells.iter().filter(|c| **c).count()
+    }
+
+    /// Getter for `width`
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    /// Getter for `height`
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Game;
+
+    #[test]
+    fn ascii_layout() {
+        let mask = Mask::from_ascii("##\n#. #\n").unwrap();
+        assert_eq!((mask.width(), mask.height()), (4, 2));
+        assert_eq!(mask.tile_count(), 4);
+        // The first line is the top row, short lines are padded with holes
+        assert!(mask.contains(Coordinates { x: 1, y: 1 }));
+        assert!(!mask.contains(Coordinates { x: 2, y: 1 }));
+        assert!(!mask.contains(Coordinates { x: 1, y: 0 }));
+        assert!(mask.contains(Coordinates { x: 3, y: 0 }));
+        assert!(!mask.contains(Coordinates { x: 4, y: 0 }));
+        assert!(matches!(Mask::from_ascii("#x"), Err(BoardError::InvalidMask(_))));
+    }
+
+    #[test]
+    fn holes_are_not_neighbors() {
+        let mask = Mask::from_ascii("#.#\n###\n").unwrap();
+        let mut tile_map = TileMap::from_mask(mask);
+        assert_eq!(tile_map.tile_count(), 5);
+        tile_map.set_bombs(4, 0).unwrap();
+        // Every existing tile but one holds a bomb, and no hole does
+        assert_eq!(tile_map.coordinates().filter(|c| tile_map.is_bomb_at(*c)).count(), 4);
+        assert!(!tile_map.exists(Coordinates { x: 1, y: 1 }));
+        assert!(!tile_map.is_bomb_at(Coordinates { x: 1, y: 1 }));
+    }
+
+    #[test]
+    fn oversized_masks_are_rejected() {
+        let line = "#".repeat(TileMap::MAX_SIZE as usize + 1);
+        assert!(matches!(Mask::from_ascii(&line), Err(BoardError::InvalidMask(_))));
+        let tile_map = TileMap::empty(TileMap::MAX_SIZE + 1, 1);
+        assert_eq!(
+            Game::validate(&tile_map, 1),
+            Err(BoardError::MapTooLarge { width: TileMap::MAX_SIZE + 1, height: 1 })
+        );
+        // 256 by 256 tiles overflow a u16 tile count but are still playable
+        assert_eq!(Game::validate(&TileMap::empty(256, 256), 1), Ok(()));
+    }
+}
@@ -0,0 +1,135 @@
+use std::borrow::Cow;
+use serde::{Deserialize, Serialize};
+
+use crate::BoardError;
+
+/// Delta coordinates for all 8 square neighbors
+const SQUARE_COORDINATES: [(i8, i8); 8] = [
+    // Bottom left
+    (-1, -1),
+    // Bottom
+    (0, -1),
+    // Bottom right
+    (1, -1),
+    // Left
+    (-1, 0),
+    // Right
+    (1, 0),
+    // Top Left
+    (-1, 1),
+    // Top
+    (0, 1),
+    // Top right
+    (1, 1),
+];
+
+/// Delta coordinates for the 4 orthogonal neighbors
+const ORTHOGONAL_COORDINATES: [(i8, i8); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+
+/// Delta coordinates for the 8 knight's move neighbors
+const KNIGHT_COORDINATES: [(i8, i8); 8] = [
+    (-1, -2),
+    (1, -2),
+    (-2, -1),
+    (2, -1),
+    (-2, 1),
+    (2, 1),
+    (-1, 2),
+    (1, 2),
+];
+
+/// Tiles counted as neighbors on square maps, by their delta coordinates
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Neighborhood {
+    /// The 8 surrounding tiles
+    #[default]
+    Moore,
+    /// The 4 tiles sharing an edge
+    Orthogonal,
+    /// The 8 tiles a chess knight move away
+    Knight,
+    /// Every tile within the given distance on both axes
+    Radius(u8),
+    /// Custom delta coordinates
+    Custom(Vec<(i8, i8)>)
+}
+
+impl Neighborhood {
+    /// Retrieves the delta coordinates of the neighbors
+    pub fn deltas(&self) -> Cow<'_, [(i8, i8)]> {
+        match self {
+            Self::Moore => Cow::Borrowed(&SQUARE_COORDINATES),
+            Self::Orthogonal => Cow::Borrowed(&ORTHOGONAL_COORDINATES),
+            Self::Knight => Cow::Borrowed(&KNIGHT_COORDINATES),
+            Self::Radius(radius) => {
+                let radius = (*radius).min(i8::MAX as u8) as i8;
+                Cow::Owned(
+                    (-radius..=radius)
+                        .flat_map(|y| (-radius..=radius).map(move |x| (x, y)))
+                        .filter(|delta| *delta != (0, 0))
+                        .collect()
+                )
+            }
+            Self::Custom(deltas) => Cow::Borrowed(deltas)
+        }
+    }
+
+    /// Checks that tiles have neighbors and that neighbor counts fit in a tile,
+    /// bomb tiles holding up to `max_mines` mines
+    pub fn validate(&self, max_mines: u8) -> Result<(), BoardError> {
+        let size = self.deltas().iter().filter(|delta| **delta != (0, 0)).count();
+        if size == 0 || size * max_mines as usize > u8::MAX as usize {
+            return Err(BoardError::InvalidNeighborhood { size, max_mines });
+        }
+        Ok(())
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Coordinates, TileMap};
+
+    fn sorted_deltas(neighborhood: &Neighborhood) -> Vec<(i8, i8)> {
+        let mut deltas = neighborhood.deltas().into_owned();
+        deltas.sort();
+        deltas
+    }
+
+    #[test]
+    fn neighborhood_deltas() {
+        assert_eq!(sorted_deltas(&Neighborhood::Radius(1)), sorted_deltas(&Neighborhood::Moore));
+        assert_eq!(Neighborhood::Radius(2).deltas().len(), 24);
+        assert_eq!(
+            sorted_deltas(&Neighborhood::Knight),
+            [(-2, -1), (-2, 1), (-1, -2), (-1, 2), (1, -2), (1, 2), (2, -1), (2, 1)]
+        );
+        let custom = Neighborhood::Custom(vec![(0, 1), (0, 2)]);
+        assert_eq!(custom.deltas(), [(0, 1), (0, 2)].as_slice());
+    }
+
+    #[test]
+    fn knight_counts() {
+        let tile_map = TileMap::from_text("3x3\n...\n...\n*..\n")
+            .unwrap()
+            .with_neighborhood(Neighborhood::Knight);
+        // Only tiles a knight move away from the mine count it
+        assert_eq!(tile_map.bomb_count_at(Coordinates { x: 1, y: 2 }), 1);
+        assert_eq!(tile_map.bomb_count_at(Coordinates { x: 2, y: 1 }), 1);
+        assert_eq!(tile_map.bomb_count_at(Coordinates { x: 1, y: 1 }), 0);
+        assert_eq!(tile_map.bomb_count_at(Coordinates { x: 1, y: 0 }), 0);
+    }
+
+    #[test]
+    fn neighborhood_validation() {
+        assert!(Neighborhood::Moore.validate(3).is_ok());
+        assert!(Neighborhood::Radius(7).validate(1).is_ok());
+        assert_eq!(
+            Neighborhood::Radius(7).validate(3),
+            Err(BoardError::InvalidNeighborhood { size: 224, max_mines: 3 })
+        );
+        assert_eq!(
+            Neighborhood::Custom(vec![(0, 0)]).validate(1),
+            Err(BoardError::InvalidNeighborhood { size: 0, max_mines: 1 })
+        );
+    }
+}
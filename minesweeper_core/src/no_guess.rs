@@ -0,0 +1,135 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{BoardError, Coordinates, Game, GameStatus, Solver, Tile, TileMap};
+
+/// What to do when no candidate layout can be solved without guessing
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum NoGuessFallback {
+    /// Keeps the candidate the solver cleared the furthest
+    #[default]
+    BestAttempt,
+    /// Fails the generation. Boards generated on first click keep the best attempt instead,
+    /// since the click cannot be refused
+    Fail
+}
+
+/// No-guess generation options, ignored on maps with multi-mine tiles
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct NoGuess {
+    /// Maximum number of candidate layouts to try
+    pub attempts: u32,
+    /// Strategy when every attempt needs a guess
+    pub fallback: NoGuessFallback
+}
+
+impl Default for NoGuess {
+    fn default() -> Self {
+        Self {
+            attempts: 200,
+            fallback: Default::default()
+        }
+    }
+}
+
+/// Generates candidate layouts until one can be cleared by logic alone from `start`,
+/// or from the first empty tile when `start` is `None`
+pub(crate) fn generate(
+    tile_map: &TileMap,
+    bomb_count: u16,
+    excluded: &[Coordinates],
+    start: Option<Coordinates>,
+    seed: u64,
+    no_guess: NoGuess
+) -> Result<TileMap, BoardError> {
+    let mut best: Option<(usize, TileMap)> = None;
+    for attempt in 0..no_guess.attempts.max(1) {
+        let mut candidate = tile_map.clone();
+        candidate.set_bombs_excluding(bomb_count, excluded, seed.wrapping_add(attempt as u64))?;
+        let Some(start) = start.or_else(|| first_empty(&candidate)) else {
+            continue;
+        };
+        let left = covered_after_solving(&candidate, start);
+        if left == 0 {
+            return Ok(candidate);
+        }
+        if best.as_ref().is_none_or(|(l, _)| left < *l) {
+            best = Some((left, candidate));
+        }
+    }
+    match (no_guess.fallback, best) {
+        (NoGuessFallback::BestAttempt, Some((_, candidate))) => Ok(candidate),
+        _ => Err(BoardError::NoGuessNotFound { attempts: no_guess.attempts })
+    }
+}
+
+/// Retrieves the first empty tile, the opening of safe start boards
+pub(crate) fn first_empty(tile_map: &TileMap) -> Option<Coordinates> {
+    tile_map
+        .coordinates()
+        .find(|c| tile_map.tile_at(*c) == Some(&Tile::Empty))
+}
+
+/// Plays from `start` revealing only proven safe tiles, returning the safe tiles left covered
+fn covered_after_solving(tile_map: &TileMap, start: Coordinates) -> usize {
+    let mut game = Game::new(tile_map.clone());
+    game.reveal(start);
+    while game.status() == GameStatus::Playing {
+        let safe = {
+            let knowledge = game.knowledge();
+            Solver::new(&knowledge).solve().safe
+        };
+        if safe.is_empty() {
+            break;
+        }
+        for coords in safe {
+            game.reveal(coords);
+        }
+    }
+    match game.status() {
+        GameStatus::Won => 0,
+        _ => game.covered_tiles().len().saturating_sub(tile_map.bomb_count() as usize).max(1)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_boards_are_solvable() {
+        let no_guess = NoGuess::default();
+        for seed in 0..5 {
+            let tile_map = generate(&TileMap::empty(9, 9), 10, &[], None, seed, no_guess).unwrap();
+            assert_eq!(tile_map.bomb_count(), 10);
+            let start = first_empty(&tile_map).unwrap();
+            assert_eq!(covered_after_solving(&tile_map, start), 0);
+        }
+    }
+
+    #[test]
+    fn fallback_when_every_attempt_needs_a_guess() {
+        // One mine among the three tiles around the start is never decided
+        let start = Coordinates { x: 0, y: 0 };
+        let tile_map = TileMap::empty(2, 2);
+        let no_guess = NoGuess { attempts: 5, fallback: NoGuessFallback::Fail };
+        assert_eq!(
+            generate(&tile_map, 1, &[start], Some(start), 0, no_guess).unwrap_err(),
+            BoardError::NoGuessNotFound { attempts: 5 }
+        );
+        let no_guess = NoGuess { fallback: NoGuessFallback::BestAttempt, ..no_guess };
+        let best = generate(&tile_map, 1, &[start], Some(start), 0, no_guess).unwrap();
+        assert_eq!(best.bomb_count(), 1);
+        assert!(!best.is_bomb_at(start));
+    }
+
+    #[test]
+    fn at_least_one_attempt() {
+        let no_guess = NoGuess { attempts: 0, fallback: NoGuessFallback::BestAttempt };
+        let start = Coordinates { x: 0, y: 0 };
+        assert!(generate(&TileMap::empty(2, 2), 1, &[start], Some(start), 0, no_guess).is_ok());
+        // Boards without an empty tile to start from have no candidate to fall back on
+        assert_eq!(
+            generate(&TileMap::empty(2, 1), 1, &[], None, 0, no_guess).unwrap_err(),
+            BoardError::NoGuessNotFound { attempts: 0 }
+        );
+    }
+}
@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+
+use crate::{Cell, Coordinates, Solver};
+use crate::solver::{enumerate, sample, split_components, Component};
+
+/// Mine probability of the covered tiles of a board
+#[derive(Debug, Clone, Default)]
+pub struct Probabilities {
+    /// Probability of every covered tile to hold a mine
+    pub tiles: HashMap<Coordinates, f64>,
+    /// Were every frontier component enumerated, rather than sampled?
+    pub exact: bool
+}
+
+impl Probabilities {
+    /// Retrieves the mine probability of the tile at `coords`, if covered
+    pub fn get(&self, coords: Coordinates) -> Option<f64> {
+        self.tiles.get(&coords).copied()
+    }
+
+    /// Retrieves the covered tiles least likely to hold a mine, excluding proven mines
+    pub fn safest(&self) -> Vec<Coordinates> {
+        let Some(min) = self
+            .tiles
+            .values()
+            .copied()
+            .filter(|p| *p < 1.0)
+            .min_by(f64::total_cmp)
+        else {
+            return Vec::new();
+        };
+        let mut safest: Vec<Coordinates> = self
+            .tiles
+            .iter()
+            .filter(|(_, p)| **p <= min + f64::EPSILON)
+            .map(|(c, _)| *c)
+            .collect();
+        safest.sort();
+        safest
+    }
+}
+
+impl Solver<'_> {
+    /// Computes the mine probability of every covered tile, weighting the frontier layouts
+    /// by the ways to place the remaining mines on the interior tiles.
+    /// Interior tiles are only included when the total bomb count is known.
+    /// Maps with multi-mine tiles are not supported and give no probabilities
+    pub fn probabilities(&self) -> Probabilities {
+        if self.knowledge.max_mines() > 1 {
+            return Probabilities {
+                tiles: HashMap::new(),
+                exact: false
+            };
+        }
+        let solution = self.solve();
+        let frontier = self.frontier(&solution);
+        let mut probabilities = Probabilities {
+            tiles: HashMap::new(),
+            exact: true
+        };
+        for (coords, cell) in self.knowledge.cells() {
+            if solution.safe.contains(&coords) {
+                probabilities.tiles.insert(coords, 0.0);
+            } else if solution.mines.contains(&coords)
+                || (cell == Cell::Flagged && self.trust_flags)
+            {
+                probabilities.tiles.insert(coords, 1.0);
+            }
+        }
+        let components: Vec<Component> = split_components(&frontier.constraints)
+            .into_iter()
+            .enumerate()
+            .map(|(i, (cells, constraints))| {
+                if cells.len() <= self.max_enumeration {
+                    enumerate(cells, &constraints)
+                } else {
+                    probabilities.exact = false;
+                    sample(cells, &constraints, self.samples, i as u64)
+                }
+            })
+            .collect();
+        // Layout counts of each component by mine count, scaled to stay within float range
+        let scaled: Vec<(Vec<f64>, f64)> = components
+            .iter()
+            .map(|component| {
+                let max_k = component.layouts.keys().max().copied().unwrap_or(0);
+                let mut counts = vec![0.0; max_k + 1];
+                for (k, (count, _)) in component.layouts.iter() {
+                    counts[*k] = *count as f64;
+                }
+                let scale = counts.iter().copied().fold(0.0, f64::max).max(1.0);
+                (counts.iter().map(|c| c / scale).collect(), scale)
+            })
+            .collect();
+        let all = convolve_all(scaled.iter().map(|(c, _)| c.as_slice()));
+        let weights = interior_weights(all.len(), frontier.remaining_mines, frontier.interior.len());
+        let total: f64 = all.iter().zip(weights.iter()).map(|(a, w)| a * w).sum();
+        if total <= 0.0 {
+            // The visible state is inconsistent
+            return probabilities;
+        }
+        for (i, component) in components.iter().enumerate() {
+            let others = convolve_all(
+                scaled
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, _)| *j != i)
+                    .map(|(_, (c, _))| c.as_slice())
+            );
+            let scale = scaled[i].1;
+            let mut mines = vec![0.0; component.cells.len()];
+            for (k, (_, per_cell)) in component.layouts.iter() {
+                let weight: f64 = others
+                    .iter()
+                    .enumerate()
+                    .map(|(t, o)| o * weights.get(k + t).copied().unwrap_or(0.0))
+                    .sum();
+                for (sum, count) in mines.iter_mut().zip(per_cell) {
+                    *sum += *count as f64 / scale * weight;
+                }
+            }
+            for (coords, sum) in component.cells.iter().zip(mines) {
+                probabilities.tiles.insert(*coords, (sum / total).clamp(0.0, 1.0));
+            }
+        }
+        if let Some(remaining) = frontier.remaining_mines {
+            let interior = frontier.interior.len();
+            if interior > 0 {
+                let expected: f64 = all
+                    .iter()
+                    .zip(weights.iter())
+                    .enumerate()
+                    .filter(|(t, _)| *t <= remaining)
+                    .map(|(t, (a, w))| a * w * (remaining - t) as f64)
+                    .sum();
+                let probability = (expected / total / interior as f64).clamp(0.0, 1.0);
+                for coords in frontier.interior.iter() {
+                    probabilities.tiles.insert(*coords, probability);
+                }
+            }
+        }
+        probabilities
+    }
+}
+
+/// Convolution of layout counts indexed by mine count
+fn convolve_all<'a>(counts: impl Iterator<Item = &'a [f64]>) -> Vec<f64> {
+    let mut result = vec![1.0];
+    for count in counts {
+        let mut next = vec![0.0; result.len() + count.len() - 1];
+        for (i, a) in result.iter().enumerate() {
+            for (j, b) in count.iter().enumerate() {
+                next[i + j] += a * b;
+            }
+        }
+        result = next;
+    }
+    result
+}
+
+/// Relative number of ways to place the remaining mines on the interior tiles, by frontier mine count
+fn interior_weights(len: usize, remaining_mines: Option<usize>, interior: usize) -> Vec<f64> {
+    let Some(remaining) = remaining_mines else {
+        return vec![1.0; len];
+    };
+    // ln(n!) for every n up to the interior size
+    let ln_factorials: Vec<f64> = (0..=interior)
+        .scan(0.0, |acc, n| {
+            if n > 0 {
+                *acc += (n as f64).ln();
+            }
+            Some(*acc)
+        })
+        .collect();
+    let ln_weights: Vec<Option<f64>> = (0..len)
+        .map(|t| {
+            let m = remaining.checked_sub(t)?;
+            (m <= interior).then(|| {
+                ln_factorials[interior] - ln_factorials[m] - ln_factorials[interior - m]
+            })
+        })
+        .collect();
+    let max = ln_weights.iter().flatten().copied().fold(f64::NEG_INFINITY, f64::max);
+    ln_weights
+        .into_iter()
+        .map(|w| w.map_or(0.0, |w| (w - max).exp()))
+        .collect()
+}
+#[cfg(test)]
+mod tests {
+    use crate::{Coordinates, Game, Solver, TileMap};
+    use crate::solver::tests::knowledge;
+
+    #[test]
+    fn probabilities_of_a_small_board() {
+        let tile_map = TileMap::from_text("4x1\n.*..\n").unwrap();
+        let revealed = [Coordinates { x: 2, y: 0 }];
+        let knowledge = knowledge(&tile_map, &revealed, Some(1));
+        let probabilities = Solver::new(&knowledge).probabilities();
+        assert!(probabilities.exact);
+        assert_eq!(probabilities.get(Coordinates { x: 0, y: 0 }), Some(0.0));
+        assert_eq!(probabilities.get(Coordinates { x: 1, y: 0 }), Some(0.5));
+        assert_eq!(probabilities.get(Coordinates { x: 3, y: 0 }), Some(0.5));
+        assert_eq!(probabilities.get(Coordinates { x: 2, y: 0 }), None);
+    }
+
+    #[test]
+    fn probabilities_sum_to_the_bomb_count() {
+        for seed in 0..10 {
+            let mut tile_map = TileMap::empty(9, 9);
+            tile_map.set_bombs(10, seed).unwrap();
+            let mut game = Game::new(tile_map);
+            let opening = game
+                .tile_map()
+                .coordinates()
+                .find(|c| game.tile_map().bomb_count_at(*c) == 0 && !game.tile_map().is_bomb_at(*c))
+                .unwrap();
+            game.reveal(opening);
+            let knowledge = game.knowledge();
+            let probabilities = Solver::new(&knowledge).probabilities();
+            let sum: f64 = probabilities.tiles.values().sum();
+            assert!(probabilities.exact);
+            assert!((sum - 10.0).abs() < 1e-9, "seed {}: probabilities sum to {}", seed, sum);
+        }
+    }
+}
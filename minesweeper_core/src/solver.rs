@@ -0,0 +1,590 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{Cell, Coordinates, Knowledge};
+
+/// Default maximum number of tiles in a frontier component to enumerate
+const DEFAULT_MAX_ENUMERATION: usize = 30;
+/// Default number of layouts sampled from components too large to enumerate
+const DEFAULT_SAMPLES: usize = 1000;
+/// Maximum number of search nodes visited for a single sample
+const SAMPLE_BUDGET: usize = 100_000;
+
+/// Tiles proven safe or mined from the visible state of a board
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Solution {
+    /// Covered tiles that cannot hold a bomb
+    pub safe: BTreeSet<Coordinates>,
+    /// Covered tiles that must hold a bomb
+    pub mines: BTreeSet<Coordinates>
+}
+
+impl Solution {
+    /// Did the solver prove anything?
+    pub fn is_empty(&self) -> bool {
+        self.safe.is_empty() && self.mines.is_empty()
+    }
+}
+
+/// Exact number of mines among a set of unknown tiles
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub(crate) struct Constraint {
+    pub cells: BTreeSet<Coordinates>,
+    pub mines: usize
+}
+
+/// Every consistent mine layout of a group of frontier tiles linked by constraints
+#[derive(Debug, Clone)]
+pub(crate) struct Component {
+    pub cells: Vec<Coordinates>,
+    /// Layouts by mine count: number of layouts and, per cell, the layouts putting a mine on it
+    pub layouts: BTreeMap<usize, (u64, Vec<u64>)>
+}
+
+/// Unknown tiles of a board split into frontier constraints and unconstrained interior tiles
+#[derive(Debug, Clone)]
+pub(crate) struct Frontier {
+    pub constraints: Vec<Constraint>,
+    pub interior: Vec<Coordinates>,
+    /// Mines left among the unknown tiles, when the total bomb count is known
+    pub remaining_mines: Option<usize>
+}
+
+/// Constraint-propagation solver over the visible state of a board
+#[derive(Debug, Clone)]
+pub struct Solver<'a> {
+    pub(crate) knowledge: &'a Knowledge<'a>,
+    pub(crate) trust_flags: bool,
+    pub(crate) max_enumeration: usize,
+    pub(crate) samples: usize
+}
+
+impl<'a> Solver<'a> {
+    pub fn new(knowledge: &'a Knowledge<'a>) -> Self {
+        Self {
+            knowledge,
+            trust_flags: false,
+            max_enumeration: DEFAULT_MAX_ENUMERATION,
+            samples: DEFAULT_SAMPLES
+        }
+    }
+
+    /// Treats the player flags as known mines instead of covered tiles
+    pub fn trust_flags(mut self, trust_flags: bool) -> Self {
+        self.trust_flags = trust_flags;
+        self
+    }
+
+    /// Sets the maximum number of tiles in a frontier component to enumerate
+    pub fn max_enumeration(mut self, max_enumeration: usize) -> Self {
+        self.max_enumeration = max_enumeration;
+        self
+    }
+
+    /// Sets the number of layouts sampled from components too large to enumerate
+    pub fn samples(mut self, samples: usize) -> Self {
+        self.samples = samples;
+        self
+    }
+
+    /// Retrieves every tile provably safe or mined, from the cheapest rules to full enumeration.
+    /// Maps with multi-mine tiles are not supported and give an empty solution
+    pub fn solve(&self) -> Solution {
+        let mut solution = Solution::default();
+        if self.knowledge.max_mines() > 1 {
+            return solution;
+        }
+        loop {
+            let frontier = self.frontier(&solution);
+            if single_rule(&frontier.constraints, &mut solution)
+                || pair_rule(&frontier.constraints, &mut solution)
+                || self.enumeration_rule(&frontier, &mut solution)
+            {
+                continue;
+            }
+            return solution;
+        }
+    }
+
+    /// Builds the constraints of the revealed numbers, the tiles in `solution` being known
+    pub(crate) fn frontier(&self, solution: &Solution) -> Frontier {
+        let mut constraints = BTreeSet::new();
+        for (coords, cell) in self.knowledge.cells() {
+            let Cell::Revealed(count) = cell else {
+                continue;
+            };
+            let mut cells = BTreeSet::new();
+            let mut mines = 0;
+            for neighbor in self.knowledge.neighbors(coords) {
+                if self.is_known_mine(neighbor, solution) {
+                    mines += 1;
+                } else if self.is_unknown(neighbor, solution) {
+                    cells.insert(neighbor);
+                }
+            }
+            // Inconsistent numbers, from wrong trusted flags, are ignored
+            let Some(mines) = (count as usize).checked_sub(mines) else {
+                continue;
+            };
+            if !cells.is_empty() && mines <= cells.len() {
+                constraints.insert(Constraint { cells, mines });
+            }
+        }
+        let constrained: BTreeSet<Coordinates> = constraints
+            .iter()
+            .flat_map(|c| c.cells.iter().copied())
+            .collect();
+        let mut interior: Vec<Coordinates> = self
+            .knowledge
+            .cells()
+            .map(|(c, _)| c)
+            .filter(|c| self.is_unknown(*c, solution) && !constrained.contains(c))
+            .collect();
+        interior.sort();
+        let known_mines = self
+            .knowledge
+            .cells()
+            .filter(|(c, _)| self.is_known_mine(*c, solution))
+            .count();
+        let remaining_mines = self
+            .knowledge
+            .bomb_count()
+            .and_then(|count| (count as usize).checked_sub(known_mines));
+        Frontier {
+            constraints: constraints.into_iter().collect(),
+            interior,
+            remaining_mines
+        }
+    }
+
+    pub(crate) fn is_known_mine(&self, coords: Coordinates, solution: &Solution) -> bool {
+        match self.knowledge.cell(coords) {
+            Some(Cell::Mine) => true,
+            Some(Cell::Flagged) if self.trust_flags => true,
+            _ => solution.mines.contains(&coords)
+        }
+    }
+
+    pub(crate) fn is_unknown(&self, coords: Coordinates, solution: &Solution) -> bool {
+        match self.knowledge.cell(coords) {
+            Some(Cell::Covered) => {}
+            Some(Cell::Flagged) if !self.trust_flags => {}
+            _ => return false
+        }
+        !solution.safe.contains(&coords) && !solution.mines.contains(&coords)
+    }
+
+    /// Enumerates every frontier component, also using the total bomb count when known
+    fn enumeration_rule(&self, frontier: &Frontier, solution: &mut Solution) -> bool {
+        let components: Vec<Component> = split_components(&frontier.constraints)
+            .into_iter()
+            .map(|(cells, constraints)| {
+                if cells.len() <= self.max_enumeration {
+                    enumerate(cells, &constraints)
+                } else {
+                    // Too large: every mine count is assumed possible, with no layout known
+                    Component {
+                        layouts: (0..=cells.len()).map(|k| (k, (0, Vec::new()))).collect(),
+                        cells
+                    }
+                }
+            })
+            .collect();
+        let valid_counts = valid_mine_counts(&components, frontier);
+        let mut progress = false;
+        for (component, valid) in components.iter().zip(valid_counts.iter()) {
+            let mut total = 0;
+            let mut per_cell = vec![0; component.cells.len()];
+            for (k, (count, cell_counts)) in component.layouts.iter() {
+                if !valid.contains(k) || cell_counts.is_empty() {
+                    continue;
+                }
+                total += count;
+                for (sum, c) in per_cell.iter_mut().zip(cell_counts) {
+                    *sum += c;
+                }
+            }
+            // Skipped components never prove anything
+            if total == 0 || component.layouts.values().any(|(c, _)| *c == 0) {
+                continue;
+            }
+            for (coords, mines) in component.cells.iter().zip(per_cell) {
+                if mines == 0 {
+                    progress |= solution.safe.insert(*coords);
+                } else if mines == total {
+                    progress |= solution.mines.insert(*coords);
+                }
+            }
+        }
+        // The interior tiles are decided when the frontier leaves them no choice
+        if let Some(interior_mines) = interior_mine_counts(&components, &valid_counts, frontier) {
+            let interior = &frontier.interior;
+            if interior_mines.iter().all(|k| *k == 0) {
+                for coords in interior {
+                    progress |= solution.safe.insert(*coords);
+                }
+            } else if interior_mines.iter().all(|k| *k == interior.len()) {
+                for coords in interior {
+                    progress |= solution.mines.insert(*coords);
+                }
+            }
+        }
+        progress
+    }
+}
+
+/// A number with no mine left makes its tiles safe, and one with as many mines as tiles makes them mines
+fn single_rule(constraints: &[Constraint], solution: &mut Solution) -> bool {
+    let mut progress = false;
+    for constraint in constraints {
+        if constraint.mines == 0 {
+            for coords in constraint.cells.iter() {
+                progress |= solution.safe.insert(*coords);
+            }
+        } else if constraint.mines == constraint.cells.len() {
+            for coords in constraint.cells.iter() {
+                progress |= solution.mines.insert(*coords);
+            }
+        }
+    }
+    progress
+}
+
+/// Bounds the mines shared by two overlapping numbers, which covers subsets and the usual patterns
+fn pair_rule(constraints: &[Constraint], solution: &mut Solution) -> bool {
+    let mut by_cell: HashMap<Coordinates, Vec<usize>> = HashMap::new();
+    for (i, constraint) in constraints.iter().enumerate() {
+        for coords in constraint.cells.iter() {
+            by_cell.entry(*coords).or_default().push(i);
+        }
+    }
+    let pairs: BTreeSet<(usize, usize)> = by_cell
+        .values()
+        .flat_map(|indices| {
+            indices
+                .iter()
+                .flat_map(move |a| indices.iter().filter(move |b| a < *b).map(move |b| (*a, *b)))
+        })
+        .collect();
+    let mut progress = false;
+    for (a, b) in pairs {
+        let (a, b) = (&constraints[a], &constraints[b]);
+        let both = a.cells.intersection(&b.cells).count();
+        let only_a: Vec<Coordinates> = a.cells.difference(&b.cells).copied().collect();
+        let only_b: Vec<Coordinates> = b.cells.difference(&a.cells).copied().collect();
+        let both_max = both.min(a.mines).min(b.mines);
+        let both_min = a
+            .mines
+            .saturating_sub(only_a.len())
+            .max(b.mines.saturating_sub(only_b.len()));
+        if both_min > both_max {
+            continue;
+        }
+        for (constraint, only) in [(a, &only_a), (b, &only_b)] {
+            if only.is_empty() {
+                continue;
+            }
+            if constraint.mines == both_min {
+                for coords in only.iter() {
+                    progress |= solution.safe.insert(*coords);
+                }
+            } else if constraint.mines.saturating_sub(both_max) == only.len() {
+                for coords in only.iter() {
+                    progress |= solution.mines.insert(*coords);
+                }
+            }
+        }
+    }
+    progress
+}
+
+/// Groups the constrained tiles sharing numbers, each group with its constraints
+pub(crate) fn split_components(
+    constraints: &[Constraint]
+) -> Vec<(Vec<Coordinates>, Vec<Constraint>)> {
+    let mut component_of: HashMap<Coordinates, usize> = HashMap::new();
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    for (i, constraint) in constraints.iter().enumerate() {
+        let mut merged: BTreeSet<usize> = constraint
+            .cells
+            .iter()
+            .filter_map(|c| component_of.get(c).copied())
+            .collect();
+        let target = match merged.pop_first() {
+            Some(t) => t,
+            None => {
+                groups.push(Vec::new());
+                groups.len() - 1
+            }
+        };
+        // We merge every other group touched by this constraint into the target
+        for other in merged {
+            let moved = std::mem::take(&mut groups[other]);
+            for j in moved.iter() {
+                for c in constraints[*j].cells.iter() {
+                    component_of.insert(*c, target);
+                }
+            }
+            groups[target].extend(moved);
+        }
+        for c in constraint.cells.iter() {
+            component_of.insert(*c, target);
+        }
+        groups[target].push(i);
+    }
+    groups
+        .into_iter()
+        .filter(|g| !g.is_empty())
+        .map(|group| {
+            let constraints: Vec<Constraint> = group.iter().map(|i| constraints[*i].clone()).collect();
+            let cells: BTreeSet<Coordinates> = constraints
+                .iter()
+                .flat_map(|c| c.cells.iter().copied())
+                .collect();
+            (cells.into_iter().collect(), constraints)
+        })
+        .collect()
+}
+
+/// Backtracks through every mine layout of `cells` satisfying `constraints`
+pub(crate) fn enumerate(cells: Vec<Coordinates>, constraints: &[Constraint]) -> Component {
+    let cell_constraints = cell_constraints(&cells, constraints);
+    let mut search = Search::new(constraints, &cell_constraints, None);
+    search.run(0, 0);
+    Component {
+        cells,
+        layouts: search.layouts
+    }
+}
+
+/// Collects random mine layouts of `cells` satisfying `constraints`, for components too large to enumerate.
+/// The layouts are not exactly uniform, so derived probabilities are approximations.
+pub(crate) fn sample(
+    cells: Vec<Coordinates>,
+    constraints: &[Constraint],
+    samples: usize,
+    seed: u64
+) -> Component {
+    let cell_constraints = cell_constraints(&cells, constraints);
+    let mut search = Search::new(constraints, &cell_constraints, Some(ChaCha8Rng::seed_from_u64(seed)));
+    for _ in 0..samples {
+        search.leaves_left = Some(1);
+        search.budget = SAMPLE_BUDGET;
+        search.run(0, 0);
+    }
+    Component {
+        cells,
+        layouts: search.layouts
+    }
+}
+
+/// Constraint indices of every cell
+fn cell_constraints(cells: &[Coordinates], constraints: &[Constraint]) -> Vec<Vec<usize>> {
+    let index: HashMap<Coordinates, usize> = cells.iter().enumerate().map(|(i, c)| (*c, i)).collect();
+    let mut cell_constraints = vec![Vec::new(); cells.len()];
+    for (i, constraint) in constraints.iter().enumerate() {
+        for c in constraint.cells.iter() {
+            cell_constraints[index[c]].push(i);
+        }
+    }
+    cell_constraints
+}
+
+struct Search<'a> {
+    constraints: &'a [Constraint],
+    cell_constraints: &'a [Vec<usize>],
+    assigned_mines: Vec<usize>,
+    unassigned: Vec<usize>,
+    layout: Vec<bool>,
+    layouts: BTreeMap<usize, (u64, Vec<u64>)>,
+    /// Randomizes the value tried first, when sampling
+    rng: Option<ChaCha8Rng>,
+    /// Layouts left to find before stopping, when sampling
+    leaves_left: Option<usize>,
+    /// Nodes left to visit before giving up, when sampling
+    budget: usize
+}
+
+impl<'a> Search<'a> {
+    fn new(
+        constraints: &'a [Constraint],
+        cell_constraints: &'a [Vec<usize>],
+        rng: Option<ChaCha8Rng>
+    ) -> Self {
+        Self {
+            constraints,
+            cell_constraints,
+            assigned_mines: vec![0; constraints.len()],
+            unassigned: constraints.iter().map(|c| c.cells.len()).collect(),
+            layout: vec![false; cell_constraints.len()],
+            layouts: BTreeMap::new(),
+            rng,
+            leaves_left: None,
+            budget: usize::MAX
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.leaves_left == Some(0) || self.budget == 0
+    }
+
+    fn run(&mut self, cell: usize, mines: usize) {
+        self.budget = self.budget.saturating_sub(1);
+        if cell == self.layout.len() {
+            let (count, per_cell) = self
+                .layouts
+                .entry(mines)
+                .or_insert_with(|| (0, vec![0; self.layout.len()]));
+            *count += 1;
+            for (sum, mine) in per_cell.iter_mut().zip(self.layout.iter()) {
+                *sum += *mine as u64;
+            }
+            if let Some(left) = self.leaves_left.as_mut() {
+                *left -= 1;
+            }
+            return;
+        }
+        let mine_first = self.rng.as_mut().is_some_and(|rng| rng.gen());
+        let values = if mine_first { [true, false] } else { [false, true] };
+        for mine in values {
+            if self.is_done() {
+                break;
+            }
+            self.layout[cell] = mine;
+            let mut consistent = true;
+            for i in self.cell_constraints[cell].iter() {
+                self.unassigned[*i] -= 1;
+                self.assigned_mines[*i] += mine as usize;
+                let target = self.constraints[*i].mines;
+                if self.assigned_mines[*i] > target
+                    || self.assigned_mines[*i] + self.unassigned[*i] < target
+                {
+                    consistent = false;
+                }
+            }
+            if consistent {
+                self.run(cell + 1, mines + mine as usize);
+            }
+            for i in self.cell_constraints[cell].iter() {
+                self.unassigned[*i] += 1;
+                self.assigned_mines[*i] -= mine as usize;
+            }
+        }
+        self.layout[cell] = false;
+    }
+}
+
+/// Possible sums of one mine count per set
+fn sum_set<'a>(sets: impl Iterator<Item = &'a BTreeSet<usize>>) -> BTreeSet<usize> {
+    let mut sums = BTreeSet::from([0]);
+    for set in sets {
+        sums = sums
+            .iter()
+            .flat_map(|s| set.iter().map(move |k| s + k))
+            .collect();
+    }
+    sums
+}
+
+/// Filters the mine counts of each component compatible with the total bomb count
+pub(crate) fn valid_mine_counts(
+    components: &[Component],
+    frontier: &Frontier
+) -> Vec<BTreeSet<usize>> {
+    let counts: Vec<BTreeSet<usize>> = components
+        .iter()
+        .map(|c| c.layouts.keys().copied().collect())
+        .collect();
+    let Some(remaining) = frontier.remaining_mines else {
+        return counts;
+    };
+    let interior = frontier.interior.len();
+    (0..counts.len())
+        .map(|i| {
+            let others = sum_set(counts.iter().enumerate().filter(|(j, _)| *j != i).map(|(_, s)| s));
+            counts[i]
+                .iter()
+                .copied()
+                .filter(|k| {
+                    others.iter().any(|o| {
+                        let frontier_mines = k + o;
+                        frontier_mines <= remaining && remaining - frontier_mines <= interior
+                    })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Possible mine counts among the interior tiles, when the total bomb count is known
+pub(crate) fn interior_mine_counts(
+    components: &[Component],
+    valid_counts: &[BTreeSet<usize>],
+    frontier: &Frontier
+) -> Option<BTreeSet<usize>> {
+    let remaining = frontier.remaining_mines?;
+    if frontier.interior.is_empty() || components.len() != valid_counts.len() {
+        return None;
+    }
+    let counts: BTreeSet<usize> = sum_set(valid_counts.iter())
+        .into_iter()
+        .filter(|t| *t <= remaining && remaining - t <= frontier.interior.len())
+        .map(|t| remaining - t)
+        .collect();
+    (!counts.is_empty()).then_some(counts)
+}
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::{Cell, Tile, TileMap};
+
+    /// Builds the knowledge of a text board where only the `revealed` tiles are uncovered
+    pub(crate) fn knowledge<'a>(
+        tile_map: &'a TileMap,
+        revealed: &[Coordinates],
+        bomb_count: Option<u16>
+    ) -> Knowledge<'a> {
+        let cells: HashMap<Coordinates, Cell> = tile_map
+            .coordinates()
+            .map(|c| {
+                let cell = match tile_map.tile_at(c) {
+                    _ if !revealed.contains(&c) => Cell::Covered,
+                    Some(Tile::BombNeighbor(count)) => Cell::Revealed(*count),
+                    Some(Tile::Bomb(_)) => Cell::Mine,
+                    _ => Cell::Revealed(0)
+                };
+                (c, cell)
+            })
+            .collect();
+        Knowledge::new(tile_map, cells, bomb_count)
+    }
+
+    #[test]
+    fn one_two_one_pattern() {
+        let tile_map = TileMap::from_text("3x2\n...\n*.*\n").unwrap();
+        let revealed = [0, 1, 2].map(|x| Coordinates { x, y: 1 });
+        let knowledge = knowledge(&tile_map, &revealed, None);
+        let solution = Solver::new(&knowledge).solve();
+        let mines = [Coordinates { x: 0, y: 0 }, Coordinates { x: 2, y: 0 }];
+        assert_eq!(solution.mines, BTreeSet::from(mines));
+        assert_eq!(solution.safe, BTreeSet::from([Coordinates { x: 1, y: 0 }]));
+    }
+
+    #[test]
+    fn interior_tile_decided_by_the_mine_count() {
+        // One mine lies next to the revealed 1, none is left for the interior tile on the left
+        let tile_map = TileMap::from_text("4x1\n.*..\n").unwrap();
+        let revealed = [Coordinates { x: 2, y: 0 }];
+        let interior = Coordinates { x: 0, y: 0 };
+        let knowledge_without_count = knowledge(&tile_map, &revealed, None);
+        let solution = Solver::new(&knowledge_without_count).solve();
+        assert!(!solution.safe.contains(&interior));
+        let knowledge_with_count = knowledge(&tile_map, &revealed, Some(1));
+        let solution = Solver::new(&knowledge_with_count).solve();
+        assert_eq!(solution.safe, BTreeSet::from([interior]));
+        assert!(solution.mines.is_empty());
+    }
+}
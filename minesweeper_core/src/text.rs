@@ -0,0 +1,149 @@
+use std::collections::HashSet;
+use std::iter;
+
+use crate::{BoardError, Coordinates, Mask, Tile, TileMap};
+
+impl TileMap {
+    /// Parses a text board: a `WIDTHxHEIGHT` line, then one line per row, top row first, where
+    /// `*` is a mine, `2` or `3` a tile holding that many mines, `.` a safe tile and a space a hole.
+    /// Short rows are padded with holes
+    pub fn from_text(text: &str) -> Result<Self, BoardError> {
+        // Only the final line break is ignored, trailing empty lines being rows of holes
+        let mut lines = text.lines();
+        let header = lines.next().unwrap_or_default();
+        let (width, height) = parse_size(header)?;
+        let rows: Vec<&str> = lines.collect();
+        if rows.len() != height as usize {
+            return Err(BoardError::InvalidText {
+                line: rows.len().min(height as usize) + 2,
+                column: 1,
+                reason: format!("expected {} rows, found {}", height, rows.len())
+            });
+        }
+        let mut bombs = Vec::new();
+        let mut holes = HashSet::new();
+        for (index, row) in rows.iter().enumerate() {
+            // The first row is the top row
+            let y = height - 1 - index as u16;
+            let line = index + 2;
+            let count = row.chars().count();
+            if count > width as usize {
+                return Err(BoardError::InvalidText {
+                    line,
+                    column: width as usize + 1,
+                    reason: format!("row is longer than the width {}", width)
+                });
+            }
+            let chars = row.chars().chain(iter::repeat_n(' ', width as usize - count));
+            for (x, c) in chars.enumerate() {
+                let coords = Coordinates { x: x as u16, y };
+                match c {
+                    '*' => bombs.push((coords, 1)),
+                    '2' | '3' => bombs.push((coords, c as u8 - b'0')),
+                    '.' => (),
+                    ' ' => {
+                        holes.insert(coords);
+                    }
+                    _ => {
+                        return Err(BoardError::InvalidText {
+                            line,
+                            column: x + 1,
+                            reason: format!("unexpected '{}'", c)
+                        })
+                    }
+                }
+            }
+        }
+        let mut tile_map = if holes.is_empty() {
+            Self::empty(width, height)
+        } else {
+            Self::from_mask(Mask::from_fn(width, height, |c| !holes.contains(&c)))
+        };
+        tile_map.set_bomb_tiles(&bombs);
+        Ok(tile_map)
+    }
+
+    /// Writes the mines of the map in the text board format read by `from_text`
+    pub fn to_text(&self) -> String {
+        let mut buffer = format!("{}x{}\n", self.width(), self.height());
+        for y in (0..self.height()).rev() {
+            let row: String = (0..self.width())
+                .map(|x| match self.tile_at(Coordinates { x, y }) {
+                    None => ' ',
+                    Some(Tile::Bomb(1)) => '*',
+                    Some(Tile::Bomb(mines)) => (b'0' + mines) as char,
+                    Some(_) => '.'
+                })
+                .collect();
+            buffer.push_str(row.trim_end_matches(' '));
+            buffer.push('\n');
+        }
+        buffer
+    }
+}
+
+/// Parses the `WIDTHxHEIGHT` header line
+fn parse_size(header: &str) -> Result<(u16, u16), BoardError> {
+    let invalid = |reason: &str| BoardError::InvalidText {
+        line: 1,
+        column: 1,
+        reason: reason.to_string()
+    };
+    let (width, height) = header
+        .split_once('x')
+        .ok_or_else(|| invalid("expected the board size as WIDTHxHEIGHT"))?;
+    let width: u16 = width.trim().parse().map_err(|_| invalid("invalid board width"))?;
+    let height: u16 = height.trim().parse().map_err(|_| {
+        BoardError::InvalidText {
+            line: 1,
+            column: header.find('x').unwrap_or_default() + 2,
+            reason: "invalid board height".to_string()
+        }
+    })?;
+    if width == 0 || height == 0 {
+        return Err(invalid("the board has no tiles"));
+    }
+    Ok((width, height))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let text = "4x3\n*.2.\n. .*\n....\n";
+        let tile_map = TileMap::from_text(text).unwrap();
+        assert_eq!(tile_map.bomb_count(), 3);
+        assert_eq!(tile_map.mine_count(), 4);
+        assert_eq!(tile_map.tile_at(Coordinates { x: 1, y: 1 }), None);
+        assert_eq!(tile_map.tile_at(Coordinates { x: 1, y: 2 }), Some(&Tile::BombNeighbor(3)));
+        assert_eq!(tile_map.to_text(), text);
+    }
+
+    #[test]
+    fn round_trip_with_a_bottom_row_of_holes() {
+        let tile_map = TileMap::from_text("3x3\n*..\n...\n\n").unwrap();
+        assert_eq!(tile_map.tile_count(), 6);
+        let text = tile_map.to_text();
+        assert_eq!(TileMap::from_text(&text).unwrap().to_text(), text);
+    }
+
+    #[test]
+    fn malformed_input() {
+        let error = |text: &str| TileMap::from_text(text).unwrap_err();
+        assert!(matches!(
+            error("3x2\n..*\n.x.\n"),
+            BoardError::InvalidText { line: 3, column: 2, .. }
+        ));
+        assert!(matches!(
+            error("2x1\n...\n"),
+            BoardError::InvalidText { line: 2, column: 3, .. }
+        ));
+        assert!(matches!(
+            error("3x2\n...\n"),
+            BoardError::InvalidText { line: 3, column: 1, .. }
+        ));
+        assert!(matches!(error("3 by 2\n"), BoardError::InvalidText { line: 1, column: 1, .. }));
+    }
+}
@@ -1,11 +1,12 @@
 #[cfg(feature = "debug")]
 use colored::Colorize;
+use serde::{Deserialize, Serialize};
 
 /// Enum describing a Minesweeper tile
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub enum Tile {
-    /// Is a bomb
-    Bomb,
+    /// Is a bomb holding 1 or more mines
+    Bomb(u8),
     /// Is a bomb neighbor
     BombNeighbor(u8),
     /// Empty tile
@@ -15,7 +16,15 @@ pub enum Tile {
 impl Tile {
     /// Is the tile a bomb?
     pub const fn is_bomb(&self) -> bool {
-        matches!(self, Self::Bomb)
+        matches!(self, Self::Bomb(_))
+    }
+
+    /// Number of mines on the tile
+    pub const fn mine_count(&self) -> u8 {
+        match self {
+            Self::Bomb(count) => *count,
+            _ => 0
+        }
     }
 
     #[cfg(feature = "debug")]
@@ -23,7 +32,8 @@ impl Tile {
         format!(
             "{}",
             match self {
-                Tile::Bomb => "*".bright_red(),
+                Tile::Bomb(1) => "*".bright_red(),
+                Tile::Bomb(count) => count.to_string().bright_red(),
                 Tile::BombNeighbor(v) => match v {
                     1 => "1".cyan(),
                     2 => "2".green(),
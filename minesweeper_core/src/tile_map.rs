@@ -0,0 +1,347 @@
+use crate::{BoardError, Coordinates, Mask, Neighborhood, Tile, Topology, Wrap};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::ops::{Deref, DerefMut};
+
+/// Base tile map
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TileMap {
+    bomb_count: u16,
+    height: u16,
+    width: u16,
+    topology: Topology,
+    wrap: Wrap,
+    neighborhood: Neighborhood,
+    /// Existing tiles, every tile existing when `None`
+    mask: Option<Mask>,
+    /// Maximum number of mines on a bomb tile
+    max_mines: u8,
+    map: Vec<Vec<Tile>>
+}
+
+impl TileMap {
+    /// Largest width or height a board can be played with
+    pub const MAX_SIZE: u16 = 512;
+
+    /// Generates an empty square map
+    pub fn empty(width: u16, height: u16) -> Self {
+        let map = (0..height)
+            .map(|_| (0..width).map(|_| Tile::Empty).collect())
+            .collect();
+        Self {
+            bomb_count: 0,
+            height,
+            width,
+            topology: Topology::default(),
+            wrap: Wrap::default(),
+            neighborhood: Neighborhood::default(),
+            mask: None,
+            max_mines: 1,
+            map
+        }
+    }
+
+    /// Sets the tile lattice of the map
+    pub fn with_topology(mut self, topology: Topology) -> Self {
+        self.topology = topology;
+        self
+    }
+
+    /// Sets the map edges across which neighbors wrap around
+    pub fn with_wrap(mut self, wrap: Wrap) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    /// Generates an empty map shaped by `mask`, holes never holding bombs nor counting as neighbors
+    pub fn from_mask(mask: Mask) -> Self {
+        Self {
+            mask: Some(mask.clone()),
+            ..Self::empty(mask.width(), mask.height())
+        }
+    }
+
+    /// Lets bomb tiles hold between 1 and `max_mines` mines, which must be 1 to 3 for bombs to be placed
+    pub fn with_max_mines(mut self, max_mines: u8) -> Self {
+        self.max_mines = max_mines;
+        self
+    }
+
+    /// Sets the tiles counted as neighbors on square maps
+    pub fn with_neighborhood(mut self, neighborhood: Neighborhood) -> Self {
+        self.neighborhood = neighborhood;
+        self
+    }
+
+    /// Places bombs and bomb neighbor tiles, the same `seed` always giving the same layout
+    pub fn set_bombs(&mut self, bomb_count: u16, seed: u64) -> Result<(), BoardError> {
+        self.set_bombs_excluding(bomb_count, &[], seed)
+    }
+
+    /// Places bombs and bomb neighbor tiles, keeping the `excluded` tiles free of bombs
+    pub fn set_bombs_excluding(
+        &mut self,
+        bomb_count: u16,
+        excluded: &[Coordinates],
+        seed: u64
+    ) -> Result<(), BoardError> {
+        if !(1..=3).contains(&self.max_mines) {
+            return Err(BoardError::InvalidMaxMines(self.max_mines));
+        }
+        let excluded_count = excluded
+            .iter()
+            .filter(|c| self.tile_at(**c).is_some_and(|t| *t == Tile::Empty))
+            .count();
+        let available = self
+            .coordinates()
+            .filter(|c| self.tile_at(*c) == Some(&Tile::Empty))
+            .count()
+            .saturating_sub(excluded_count);
+        if bomb_count as usize > available {
+            return Err(BoardError::TooManyBombs { bomb_count, available });
+        }
+        self.bomb_count = bomb_count;
+        let mut remaining_bombs = bomb_count;
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        // Place bombs
+        while remaining_bombs > 0 {
+            let (x, y) = (
+                rng.gen_range(0..self.width) as usize,
+                rng.gen_range(0..self.height) as usize
+            );
+            let coords = Coordinates { x: x as u16, y: y as u16 };
+            if excluded.contains(&coords) || !self.exists(coords) {
+                continue;
+            }
+            if let Tile::Empty = self[y][x] {
+                let mines = rng.gen_range(1..=self.max_mines);
+                self[y][x] = Tile::Bomb(mines);
+                remaining_bombs -= 1;
+            }
+        }
+        self.place_bomb_neighbors();
+        Ok(())
+    }
+
+    /// Places bombs holding the given number of mines on existing tiles, and the bomb neighbor tiles
+    pub(crate) fn set_bomb_tiles(&mut self, bombs: &[(Coordinates, u8)]) {
+        for (coords, mines) in bombs {
+            if self.exists(*coords) {
+                self[coords.y as usize][coords.x as usize] = Tile::Bomb(*mines);
+            }
+        }
+        self.bomb_count = self.coordinates().filter(|c| self.is_bomb_at(*c)).count() as u16;
+        self.max_mines = bombs.iter().map(|(_, mines)| *mines).max().unwrap_or(1).max(1);
+        self.place_bomb_neighbors();
+    }
+
+    /// Sets the bomb neighbor tiles around the placed bombs
+    fn place_bomb_neighbors(&mut self) {
+        let coordinates: Vec<Coordinates> = self.coordinates().collect();
+        for coords in coordinates {
+            if self.is_bomb_at(coords) {
+                continue;
+            }
+            let num = self.bomb_count_at(coords);
+            if num == 0 {
+                continue;
+            }
+            let tile = &mut self[coords.y as usize][coords.x as usize];
+            *tile = Tile::BombNeighbor(num);
+        }
+    }
+
+    /// Retrieves the tile at `coordinates`, if in bounds and not masked
+    pub fn tile_at(&self, coordinates: Coordinates) -> Option<&Tile> {
+        if !self.exists(coordinates) {
+            return None;
+        }
+        self.map.get(coordinates.y as usize)?.get(coordinates.x as usize)
+    }
+
+    /// Is there a tile at `coordinates`?
+    pub fn exists(&self, coordinates: Coordinates) -> bool {
+        match &self.mask {
+            Some(mask) => mask.contains(coordinates),
+            None => coordinates.x < self.width && coordinates.y < self.height
+        }
+    }
+
+    /// Iterates over the coordinates of every existing tile, row by row from the bottom
+    pub fn coordinates(&self) -> impl Iterator<Item = Coordinates> + '_ {
+        (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| Coordinates { x, y }))
+            .filter(|c| self.exists(*c))
+    }
+
+    /// Number of existing tiles
+    pub fn tile_count(&self) -> usize {
+        match &self.mask {
+            Some(mask) => mask.tile_count(),
+            None => self.width as usize * self.height as usize
+        }
+    }
+
+    pub fn is_bomb_at(&self, coordinates: Coordinates) -> bool {
+        self.tile_at(coordinates).is_some_and(Tile::is_bomb)
+    }
+
+    pub fn bomb_count_at(&self, coordinates: Coordinates) -> u8 {
+        if self.is_bomb_at(coordinates) {
+            return 0;
+        }
+        // Bomb tiles count as many times as they hold mines
+        let res: usize = self
+            .safe_square_at(coordinates)
+            .filter_map(|coord| self.tile_at(coord))
+            .map(|tile| tile.mine_count() as usize)
+            .sum();
+        res.min(u8::MAX as usize) as u8
+    }
+
+    /// Iterates over the in-map neighbors of `coordinates`, according to the map topology,
+    /// wrap and neighborhood
+    pub fn safe_square_at(&self, coordinates: Coordinates) -> impl Iterator<Item = Coordinates> + '_ {
+        let deltas = self.topology.neighbor_deltas(coordinates, &self.neighborhood);
+        let mut neighbors: Vec<Coordinates> = Vec::with_capacity(deltas.len());
+        for delta in deltas.iter() {
+            let Some(neighbor) = self.offset(coordinates, *delta).filter(|c| self.exists(*c)) else {
+                continue;
+            };
+            // Wrapping on small maps can reach the same tile twice, or the tile itself
+            if neighbor != coordinates && !neighbors.contains(&neighbor) {
+                neighbors.push(neighbor);
+            }
+        }
+        neighbors.into_iter()
+    }
+
+    /// Moves `coordinates` by `(dx, dy)`, wrapping across the joined edges
+    fn offset(&self, coordinates: Coordinates, (dx, dy): (i8, i8)) -> Option<Coordinates> {
+        let mut x = coordinates.x as i32 + dx as i32;
+        let mut y = coordinates.y as i32 + dy as i32;
+        if self.wrap.horizontal() {
+            x = x.rem_euclid(self.width as i32);
+        }
+        if self.wrap.vertical() {
+            y = y.rem_euclid(self.height as i32);
+        }
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return None;
+        }
+        Some(Coordinates { x: x as u16, y: y as u16 })
+    }
+
+    #[cfg(feature = "debug")]
+    pub fn console_output(&self) -> String {
+        let mut buffer = format!(
+            "Map ({}, {}) with {} bombs:\n",
+            self.width, self.height, self.bomb_count
+        );
+        let line: String = (0..=(self.width+1)).map(|_| '-').collect();
+        buffer = format!("{}{}\n", buffer, line);
+        for y in (0..self.height).rev() {
+            buffer = format!("{}|", buffer);
+            for x in 0..self.width {
+                // Masked holes are drawn as dots
+                let tile = self
+                    .tile_at(Coordinates { x, y })
+                    .map_or_else(|| ".".to_string(), Tile::console_output);
+                buffer = format!("{}{}", buffer, tile);
+            }
+            buffer = format!("{}|\n", buffer);
+        }
+        format!("{}{}", buffer, line)
+    }
+
+    /// Getter for `width`
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    /// Getter for `height`
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Getter for `topology`
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// Getter for `wrap`
+    pub fn wrap(&self) -> Wrap {
+        self.wrap
+    }
+
+    /// Getter for `neighborhood`
+    pub fn neighborhood(&self) -> &Neighborhood {
+        &self.neighborhood
+    }
+
+    /// Getter for `mask`
+    pub fn mask(&self) -> Option<&Mask> {
+        self.mask.as_ref()
+    }
+
+    /// Getter for `max_mines`
+    pub fn max_mines(&self) -> u8 {
+        self.max_mines
+    }
+
+    /// Total number of mines on the bomb tiles
+    pub fn mine_count(&self) -> usize {
+        self.coordinates()
+            .filter_map(|c| self.tile_at(c))
+            .map(|tile| tile.mine_count() as usize)
+            .sum()
+    }
+
+    /// Getter for `bomb_count`, the number of bomb tiles
+    pub fn bomb_count(&self) -> u16 {
+        self.bomb_count
+    }
+}
+
+impl Deref for TileMap {
+    type Target = Vec<Vec<Tile>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.map
+    }
+}
+
+impl DerefMut for TileMap {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.map
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multi_mine_tiles() {
+        let mut tile_map = TileMap::empty(6, 6).with_max_mines(3);
+        tile_map.set_bombs(10, 0).unwrap();
+        assert_eq!(tile_map.bomb_count(), 10);
+        let mines: usize = tile_map
+            .coordinates()
+            .filter_map(|c| match tile_map.tile_at(c) {
+                Some(Tile::Bomb(mines)) => Some(*mines as usize),
+                _ => None
+            })
+            .sum();
+        assert_eq!(tile_map.mine_count(), mines);
+        assert!((10..=30).contains(&mines));
+    }
+
+    #[test]
+    fn invalid_max_mines() {
+        for max_mines in [0, 4] {
+            let mut tile_map = TileMap::empty(4, 4).with_max_mines(max_mines);
+            assert_eq!(tile_map.set_bombs(2, 0), Err(BoardError::InvalidMaxMines(max_mines)));
+        }
+    }
+}
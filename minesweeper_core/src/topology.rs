@@ -0,0 +1,149 @@
+use std::borrow::Cow;
+use serde::{Deserialize, Serialize};
+
+use crate::{Coordinates, Neighborhood};
+
+/// Delta coordinates for the 6 hex neighbors of a tile on an even row
+const HEX_EVEN_ROW_COORDINATES: [(i8, i8); 6] = [
+    // Bottom left
+    (-1, -1),
+    // Bottom right
+    (0, -1),
+    // Left
+    (-1, 0),
+    // Right
+    (1, 0),
+    // Top left
+    (-1, 1),
+    // Top right
+    (0, 1),
+];
+
+/// Delta coordinates for the 6 hex neighbors of a tile on an odd row
+const HEX_ODD_ROW_COORDINATES: [(i8, i8); 6] = [
+    // Bottom left
+    (0, -1),
+    // Bottom right
+    (1, -1),
+    // Left
+    (-1, 0),
+    // Right
+    (1, 0),
+    // Top left
+    (0, 1),
+    // Top right
+    (1, 1),
+];
+
+/// Tile lattice of a map
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Topology {
+    /// Square tiles, with the neighbors of the map `Neighborhood`
+    #[default]
+    Square,
+    /// Pointy-top hex tiles with 6 neighbors, odd rows shifted half a tile to the right.
+    /// The map `Neighborhood` is ignored
+    Hex
+}
+
+impl Topology {
+    /// Retrieves the neighbor deltas of the tile at `coordinates`
+    pub fn neighbor_deltas<'a>(
+        &self,
+        coordinates: Coordinates,
+        neighborhood: &'a Neighborhood
+    ) -> Cow<'a, [(i8, i8)]> {
+        match self {
+            Self::Square => neighborhood.deltas(),
+            Self::Hex if coordinates.y % 2 == 1 => Cow::Borrowed(&HEX_ODD_ROW_COORDINATES),
+            Self::Hex => Cow::Borrowed(&HEX_EVEN_ROW_COORDINATES)
+        }
+    }
+}
+
+/// Map edges across which neighbors wrap around
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Wrap {
+    /// Edge tiles have fewer neighbors
+    #[default]
+    None,
+    /// Left and right edges are joined
+    Cylinder,
+    /// Left and right edges, and top and bottom edges, are joined.
+    /// Hex maps need an even height for the rows to line up
+    Torus
+}
+
+impl Wrap {
+    /// Are the left and right edges joined?
+    pub fn horizontal(&self) -> bool {
+        matches!(self, Self::Cylinder | Self::Torus)
+    }
+
+    /// Are the top and bottom edges joined?
+    pub fn vertical(&self) -> bool {
+        matches!(self, Self::Torus)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TileMap;
+
+    fn neighbors(tile_map: &TileMap, x: u16, y: u16) -> Vec<(u16, u16)> {
+        let mut neighbors: Vec<(u16, u16)> = tile_map
+            .safe_square_at(Coordinates { x, y })
+            .map(|c| (c.x, c.y))
+            .collect();
+        neighbors.sort();
+        neighbors
+    }
+
+    #[test]
+    fn hex_neighbors() {
+        let tile_map = TileMap::empty(4, 4).with_topology(Topology::Hex);
+        // Odd rows are shifted to the right
+        assert_eq!(neighbors(&tile_map, 1, 1), [(0, 1), (1, 0), (1, 2), (2, 0), (2, 1), (2, 2)]);
+        assert_eq!(neighbors(&tile_map, 1, 2), [(0, 1), (0, 2), (0, 3), (1, 1), (1, 3), (2, 2)]);
+        assert_eq!(neighbors(&tile_map, 0, 0), [(0, 1), (1, 0)]);
+        assert_eq!(neighbors(&tile_map, 3, 1), [(2, 1), (3, 0), (3, 2)]);
+        // The neighborhood is ignored
+        let orthogonal = tile_map.clone().with_neighborhood(Neighborhood::Orthogonal);
+        assert_eq!(neighbors(&orthogonal, 1, 1), neighbors(&tile_map, 1, 1));
+    }
+
+    #[test]
+    fn hex_neighbors_are_mutual() {
+        let tile_map = TileMap::empty(5, 4).with_topology(Topology::Hex);
+        for a in tile_map.coordinates() {
+            for b in tile_map.safe_square_at(a) {
+                assert!(tile_map.safe_square_at(b).any(|c| c == a), "{} and {}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn wrapped_neighbors() {
+        let cylinder = TileMap::empty(4, 3).with_wrap(Wrap::Cylinder);
+        assert_eq!(neighbors(&cylinder, 0, 0), [(0, 1), (1, 0), (1, 1), (3, 0), (3, 1)]);
+        let torus = TileMap::empty(4, 3).with_wrap(Wrap::Torus);
+        assert_eq!(
+            neighbors(&torus, 0, 0),
+            [(0, 1), (0, 2), (1, 0), (1, 1), (1, 2), (3, 0), (3, 1), (3, 2)]
+        );
+        // Neighbors reached twice across a small map are counted once
+        let small = TileMap::empty(2, 2).with_wrap(Wrap::Torus);
+        assert_eq!(neighbors(&small, 0, 0), [(0, 1), (1, 0), (1, 1)]);
+    }
+
+    #[test]
+    fn wrapped_hex_neighbors_are_mutual() {
+        let tile_map = TileMap::empty(5, 4).with_topology(Topology::Hex).with_wrap(Wrap::Torus);
+        for a in tile_map.coordinates() {
+            assert_eq!(tile_map.safe_square_at(a).count(), 6);
+            for b in tile_map.safe_square_at(a) {
+                assert!(tile_map.safe_square_at(b).any(|c| c == a), "{} and {}", a, b);
+            }
+        }
+    }
+}
@@ -1,9 +1,29 @@
+use std::fs;
 use bevy::app::App;
-use bevy::prelude::{DefaultPlugins, default, PluginGroup, Startup, Commands, Camera2dBundle};
+use bevy::input::{keyboard::KeyCode, Input};
+use bevy::log;
+use bevy::prelude::{
+    DefaultPlugins, default, PluginGroup, Startup, Update, Commands, Camera2dBundle,
+    Res, ResMut, State, NextState, States, EventWriter
+};
 use bevy::window::{Window, WindowPlugin, WindowResolution};
 
-use board_plugin::BoardPlugin;
-use board_plugin::resources::BoardOptions;
+use board_plugin::{BoardPlugin, GameState};
+use board_plugin::events::{HintRequestEvent, PlayReplayEvent, ReplayStepEvent, ResetBoardEvent};
+use board_plugin::resources::{
+    board::Board, BoardLayout, BoardMask, BombCount, BoardOptions, Difficulty, KeyboardControls,
+    PlaybackSpeed, Replay, ReplayFile, SafeFirstClick, SaveFile, StatsFile
+};
+
+/// File the current board layout is exported to, and played again from
+const EXPORT_FILE: &str = "board.txt";
+
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash, States)]
+pub enum AppState {
+    #[default]
+    InGame,
+    Out
+}
 
 fn main() {
     let mut app = App::new();
@@ -18,18 +38,28 @@ fn main() {
             ..default()
         }
     ));
-    app.insert_resource(
-        BoardOptions {
-            map_size: (20, 20),
-            bomb_count: 40,
-            tile_padding: 3.0,
-            safe_start: true,
-            ..Default::default()
-        }
-    );
-    app.add_plugins(BoardPlugin);
+    app.add_state::<AppState>();
+    app.insert_resource(board_options(Difficulty::Intermediate));
+    // The game in progress is saved on exit and continued on next launch
+    app.insert_resource(SaveFile("savegame.ron".into()));
+    // The last game is recorded, to be played back
+    app.insert_resource(ReplayFile("replay.ron".into()));
+    // Finished games are counted per difficulty
+    app.insert_resource(StatsFile("stats.ron".into()));
+    // Mouse-free play, digits being jump counts
+    app.insert_resource(KeyboardControls { jump_counts: true });
+    app.add_plugins(BoardPlugin {
+        running_state: AppState::InGame,
+        cleanup_state: AppState::Out
+    });
     // Startup system (cameras)
     app.add_systems(Startup, camera_setup);
+    // State handling
+    app.add_systems(Update, state_handler);
+    // Board layout export and import
+    app.add_systems(Update, layout_handler);
+    // Replay playback
+    app.add_systems(Update, replay_handler);
     // Run the app
     app.run();
 }
@@ -37,4 +67,154 @@ fn main() {
 fn camera_setup(mut commands: Commands) {
     // 2D orthographic camera
     commands.spawn(Camera2dBundle::default());
-}
\ No newline at end of file
+}
+
+fn board_options(difficulty: Difficulty) -> BoardOptions {
+    BoardOptions {
+        tile_padding: 3.0,
+        safe_first_click: SafeFirstClick::Area,
+        ..BoardOptions::preset(difficulty)
+    }
+}
+
+fn state_handler(
+    app_state: Res<State<AppState>>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+    game_state: Res<State<GameState>>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    mut reset_board_ewr: EventWriter<ResetBoardEvent>,
+    mut hint_request_ewr: EventWriter<HintRequestEvent>,
+    keys: Res<Input<KeyCode>>
+) {
+    if keys.just_pressed(KeyCode::C) && *app_state.get() == AppState::InGame {
+        log::info!("Clearing game");
+        next_app_state.set(AppState::Out);
+    }
+    if keys.just_pressed(KeyCode::G) && *app_state.get() == AppState::Out {
+        log::info!("Generating new game");
+        next_app_state.set(AppState::InGame);
+    }
+    if keys.just_pressed(KeyCode::R) && *app_state.get() == AppState::InGame {
+        log::info!("Restarting game");
+        reset_board_ewr.send(ResetBoardEvent::default());
+    }
+    if keys.just_pressed(KeyCode::H) && *app_state.get() == AppState::InGame {
+        hint_request_ewr.send(HintRequestEvent);
+    }
+    // Difficulty selection
+    for (key, difficulty) in [
+        (KeyCode::F1, Difficulty::Beginner),
+        (KeyCode::F2, Difficulty::Intermediate),
+        (KeyCode::F3, Difficulty::Expert)
+    ] {
+        if keys.just_pressed(key) && *app_state.get() == AppState::InGame {
+            log::info!("Starting {:?} game", difficulty);
+            reset_board_ewr.send(ResetBoardEvent {
+                options: Some(board_options(difficulty))
+            });
+        }
+    }
+    if keys.just_pressed(KeyCode::F4) && *app_state.get() == AppState::InGame {
+        log::info!("Starting heart shaped game");
+        reset_board_ewr.send(ResetBoardEvent {
+            options: Some(BoardOptions {
+                mask: Some(BoardMask::File("masks/heart.txt".to_string())),
+                bomb_count: BombCount::Density(0.16),
+                ..board_options(Difficulty::Intermediate)
+            })
+        });
+    }
+    if keys.just_pressed(KeyCode::F5) && *app_state.get() == AppState::InGame {
+        log::info!("Starting multi-mine game");
+        reset_board_ewr.send(ResetBoardEvent {
+            options: Some(BoardOptions {
+                max_mines_per_tile: 3,
+                ..board_options(Difficulty::Intermediate)
+            })
+        });
+    }
+    if keys.just_pressed(KeyCode::Escape) {
+        match game_state.get() {
+            GameState::Playing => {
+                log::info!("Pausing game");
+                next_game_state.set(GameState::Paused);
+            }
+            GameState::Paused => {
+                log::info!("Resuming game");
+                next_game_state.set(GameState::Playing);
+            }
+            _ => ()
+        }
+    }
+}
+
+fn layout_handler(
+    app_state: Res<State<AppState>>,
+    board: Option<Res<Board>>,
+    mut reset_board_ewr: EventWriter<ResetBoardEvent>,
+    keys: Res<Input<KeyCode>>
+) {
+    if *app_state.get() != AppState::InGame {
+        return;
+    }
+    if keys.just_pressed(KeyCode::E) {
+        let Some(board) = board else {
+            return;
+        };
+        if !board.game.is_generated() {
+            log::info!("Bombs are not placed yet, nothing to export");
+            return;
+        }
+        match fs::write(EXPORT_FILE, board.game.tile_map().to_text()) {
+            Ok(()) => log::info!("Exported board to {}", EXPORT_FILE),
+            Err(e) => log::error!("Failed to export board to {}: {}", EXPORT_FILE, e)
+        }
+    }
+    if keys.just_pressed(KeyCode::F6) {
+        match fs::read_to_string(EXPORT_FILE) {
+            Ok(text) => {
+                log::info!("Starting exported game");
+                reset_board_ewr.send(ResetBoardEvent {
+                    options: Some(BoardOptions {
+                        layout: Some(BoardLayout::Text(text)),
+                        ..board_options(Difficulty::Intermediate)
+                    })
+                });
+            }
+            Err(e) => log::error!("Failed to read {}: {}", EXPORT_FILE, e)
+        }
+    }
+}
+
+fn replay_handler(
+    app_state: Res<State<AppState>>,
+    replay_file: Res<ReplayFile>,
+    mut play_replay_ewr: EventWriter<PlayReplayEvent>,
+    mut replay_step_ewr: EventWriter<ReplayStepEvent>,
+    keys: Res<Input<KeyCode>>
+) {
+    if *app_state.get() != AppState::InGame {
+        return;
+    }
+    if keys.just_pressed(KeyCode::Period) {
+        replay_step_ewr.send(ReplayStepEvent);
+    }
+    if !keys.just_pressed(KeyCode::P) {
+        return;
+    }
+    // P plays at real time, with shift 4 times faster, with control one step per period key
+    let speed = if keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        PlaybackSpeed::Stepwise
+    } else if keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        PlaybackSpeed::Scaled(4.0)
+    } else {
+        PlaybackSpeed::Scaled(1.0)
+    };
+    match Replay::load(&replay_file.0) {
+        Ok(replay) => {
+            log::info!("Playing back {} ({:?})", replay_file.0.display(), speed);
+            play_replay_ewr.send(PlayReplayEvent { replay, speed });
+        }
+        Err(e) => log::error!("Failed to load replay: {}", e)
+    }
+}